@@ -0,0 +1,133 @@
+use super::session::Session;
+use super::{describe_error, error_status, write_result, EXIT_IO_ERROR, EXIT_SUCCESS};
+use crate::i18n::gettext_with;
+
+use std::io::{BufRead, Write};
+
+// Define the character that starts a comment, which runs to the end of the line
+const COMMENT: char = '#';
+
+// Write why the line at an index of the input can't be evaluated, with its line number
+fn report(errors: &mut impl Write, index: usize, message: &str) {
+    _ = writeln!(
+        errors,
+        "calculator: {}",
+        gettext_with(
+            "line {line}: {message}",
+            &[("line", &(index + 1).to_string()), ("message", message)],
+        )
+    );
+}
+
+// Evaluate the lines of an input one after another, writing the result of each to an output
+// and why the others have none with their line numbers to another, and return the exit status,
+// the status of the first error if any
+// Blank lines and comments are skipped, and variables set on a line are kept for the next
+// An input that can't be read to its end, e.g. one that isn't UTF-8, stops the batch with
+// EXIT_IO_ERROR whatever came before, so that a truncated input isn't taken for a whole one
+pub fn run(
+    mut session: Session,
+    input: impl BufRead,
+    output: &mut impl Write,
+    errors: &mut impl Write,
+    fail_fast: bool,
+) -> i32 {
+    let mut status = EXIT_SUCCESS;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                let error = error.to_string();
+                report(
+                    errors,
+                    index,
+                    &gettext_with("the input can't be read: {error}", &[("error", &error)]),
+                );
+                return EXIT_IO_ERROR;
+            }
+        };
+        let line = line.split(COMMENT).next().unwrap_or_default().trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match session.evaluate(line) {
+            Ok(value) => {
+                let result = write_result(value, session.options, session.format);
+                _ = writeln!(output, "{result}");
+            }
+            Err((kind, range)) => {
+                report(errors, index, &describe_error(line, kind, range));
+
+                if status == EXIT_SUCCESS {
+                    status = error_status(kind);
+                }
+                if fail_fast {
+                    break;
+                }
+            }
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{EXIT_DOMAIN_ERROR, EXIT_PARSE_ERROR};
+    use crate::logic::{Format, Options};
+
+    // Evaluate an input as a batch with the default options, and return the exit status with
+    // what was written to the output and the errors
+    fn batch(input: &[u8], fail_fast: bool) -> (i32, String, String) {
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let status = run(
+            Session::new(Options::default(), Format::default()),
+            input,
+            &mut output,
+            &mut errors,
+            fail_fast,
+        );
+        let text = |bytes: Vec<u8>| String::from_utf8(bytes).unwrap();
+        (status, text(output), text(errors))
+    }
+
+    #[test]
+    fn comments_blank_lines_and_variables() {
+        let (status, output, errors) =
+            batch(b"# offsets\n\nbase = 0x100\nbase + 4 # header\n", false);
+        assert_eq!(status, EXIT_SUCCESS);
+        assert_eq!(output, "256\n260\n");
+        assert_eq!(errors, "");
+    }
+
+    #[test]
+    fn status_is_the_one_of_the_first_error() {
+        let (status, output, errors) = batch(b"1 +\n2\n1 \xc3\xb7 0\n", false);
+        assert_eq!(status, EXIT_PARSE_ERROR);
+        assert_eq!(output, "2\n");
+        assert!(errors.starts_with("calculator: line 1: "), "{errors}");
+        assert!(errors.contains("calculator: line 3: "), "{errors}");
+    }
+
+    #[test]
+    fn fail_fast_stops_at_the_first_error() {
+        let (status, output, errors) = batch(b"1\n\n1 \xc3\xb7 0\n1 +\n2\n", true);
+        assert_eq!(status, EXIT_DOMAIN_ERROR);
+        assert_eq!(output, "1\n");
+        assert!(errors.starts_with("calculator: line 3: "), "{errors}");
+        assert!(!errors.contains("line 4"), "{errors}");
+    }
+
+    #[test]
+    fn input_that_is_not_utf8_is_an_io_error() {
+        let (status, output, errors) = batch(b"1 + 1\n\xff\xfe\n2\n", false);
+        assert_eq!(status, EXIT_IO_ERROR);
+        assert_eq!(output, "2\n");
+        assert!(errors.starts_with("calculator: line 2: "), "{errors}");
+        // Even after an error on an earlier line
+        assert_eq!(batch(b"1 +\n\xff\n", false).0, EXIT_IO_ERROR);
+    }
+}
@@ -0,0 +1,280 @@
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::mem::MaybeUninit;
+
+// Define the keys the editor reads as control characters
+const CTRL_A: u8 = 0x01;
+const CTRL_B: u8 = 0x02;
+const CTRL_C: u8 = 0x03;
+const CTRL_D: u8 = 0x04;
+const CTRL_E: u8 = 0x05;
+const CTRL_F: u8 = 0x06;
+const CTRL_H: u8 = 0x08;
+const CTRL_K: u8 = 0x0b;
+const CTRL_N: u8 = 0x0e;
+const CTRL_P: u8 = 0x10;
+const CTRL_U: u8 = 0x15;
+const ESCAPE: u8 = 0x1b;
+const BACKSPACE: u8 = 0x7f;
+
+// Define the keys the editor reads as escape sequences, the bytes after "ESC [" or "ESC O"
+// (bytes, key)
+const ESCAPE_KEYS: [(&[u8], Key); 10] = [
+    (b"A", Key::Up),
+    (b"B", Key::Down),
+    (b"C", Key::Right),
+    (b"D", Key::Left),
+    (b"H", Key::Home),
+    (b"F", Key::End),
+    (b"1~", Key::Home),
+    (b"4~", Key::End),
+    (b"3~", Key::Delete),
+    (b"7~", Key::Home),
+];
+
+// Define the keys that move around the line and the history
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Right,
+    Left,
+    Home,
+    End,
+    Delete,
+}
+
+// Define a terminal switched to raw mode, which reads every key as it is typed instead of whole
+// lines, and is switched back when dropped
+struct RawMode {
+    original: libc::termios,
+}
+
+impl RawMode {
+    // Switch the terminal of the standard input to raw mode, or return None if it can't be
+    fn enable() -> Option<RawMode> {
+        let mut original = MaybeUninit::<libc::termios>::uninit();
+        // SAFETY: tcgetattr fills in the termios it is given when it succeeds
+        let original = unsafe {
+            if libc::tcgetattr(libc::STDIN_FILENO, original.as_mut_ptr()) != 0 {
+                return None;
+            }
+            original.assume_init()
+        };
+
+        // Read bytes one at a time, without echoing them or turning keys into signals,
+        // the output is left as it is so that "\n" still starts a new line
+        let mut raw = original;
+        raw.c_iflag &= !(libc::ICRNL | libc::IXON | libc::BRKINT | libc::INPCK | libc::ISTRIP);
+        raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::ISIG | libc::IEXTEN);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: raw is a valid termios copied from the one the terminal has
+        // Keys typed ahead are kept, so that pasted lines aren't lost
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+
+        Some(RawMode { original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: original is the termios the terminal had before
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSADRAIN, &self.original);
+        }
+    }
+}
+
+// Define an editor of lines typed in a terminal, with the lines entered so far, oldest first,
+// to recall with the up and down keys
+pub struct LineEditor {
+    pub history: Vec<String>,
+}
+
+// Read a byte of the standard input, or return None at the end of it
+fn read_byte() -> Option<u8> {
+    let mut byte = [0];
+    match io::stdin().lock().read(&mut byte) {
+        Ok(1) => Some(byte[0]),
+        _ => None,
+    }
+}
+
+// Read the rest of an escape sequence, after "ESC", and find the key it stands for
+fn read_escape() -> Option<Key> {
+    if !matches!(read_byte()?, b'[' | b'O') {
+        return None;
+    }
+
+    // A sequence ends with a letter or "~", e.g. "A" for up or "3~" for delete
+    let mut bytes: Vec<u8> = Vec::new();
+    loop {
+        let byte = read_byte()?;
+        bytes.push(byte);
+        if byte.is_ascii_alphabetic() || byte == b'~' {
+            break;
+        }
+    }
+    ESCAPE_KEYS
+        .iter()
+        .find(|(sequence, _)| *sequence == bytes.as_slice())
+        .map(|(_, key)| *key)
+}
+
+// Read the rest of a character that takes more than one byte in UTF-8, after its first byte
+fn read_char(first: u8) -> Option<char> {
+    let mut bytes = vec![first];
+    let length = first.leading_ones() as usize;
+    for _ in 1..length {
+        bytes.push(read_byte()?);
+    }
+    std::str::from_utf8(&bytes).ok()?.chars().next()
+}
+
+// Show a line being edited, with the cursor at a character of it
+fn redraw(prompt: &str, line: &[char], cursor: usize) {
+    let text: String = line.iter().collect();
+    let mut stdout = io::stdout().lock();
+    _ = write!(stdout, "\r{prompt}{text}\x1b[K");
+    if cursor < line.len() {
+        _ = write!(stdout, "\x1b[{}D", line.len() - cursor);
+    }
+    _ = stdout.flush();
+}
+
+impl LineEditor {
+    pub fn new(history: Vec<String>) -> LineEditor {
+        LineEditor { history }
+    }
+
+    // Read a line after showing a prompt, letting it be edited if the standard input is a
+    // terminal, or return None at the end of the input
+    pub fn read_line(&mut self, prompt: &str) -> Option<String> {
+        let line = if io::stdin().is_terminal() {
+            match RawMode::enable() {
+                Some(raw_mode) => {
+                    let line = self.edit(prompt);
+                    drop(raw_mode);
+                    println!();
+                    line
+                }
+                None => read_plain_line(prompt),
+            }
+        } else {
+            read_plain_line("")
+        }?;
+
+        // Recall every line once, however often it is entered in a row
+        if !line.trim().is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        Some(line)
+    }
+
+    // Edit a line in a terminal in raw mode until it is entered, or return None at the end of
+    // the input
+    fn edit(&self, prompt: &str) -> Option<String> {
+        let mut line: Vec<char> = Vec::new();
+        let mut cursor = 0;
+        // The line being recalled from the history, the history's length for the new line,
+        // which is kept while older ones are recalled
+        let mut recalled = self.history.len();
+        let mut draft: Vec<char> = Vec::new();
+
+        redraw(prompt, &line, cursor);
+        loop {
+            let byte = read_byte()?;
+            let key = match byte {
+                b'\r' | b'\n' => return Some(line.into_iter().collect()),
+                // Ctrl-D ends the input on an empty line, and deletes forwards otherwise
+                CTRL_D if line.is_empty() => return None,
+                CTRL_D => Some(Key::Delete),
+                // Ctrl-C drops the line and starts a new one, like a shell does
+                CTRL_C => {
+                    print!("^C\r\n");
+                    line.clear();
+                    cursor = 0;
+                    recalled = self.history.len();
+                    None
+                }
+                CTRL_A => Some(Key::Home),
+                CTRL_E => Some(Key::End),
+                CTRL_B => Some(Key::Left),
+                CTRL_F => Some(Key::Right),
+                CTRL_P => Some(Key::Up),
+                CTRL_N => Some(Key::Down),
+                BACKSPACE | CTRL_H => {
+                    if cursor > 0 {
+                        cursor -= 1;
+                        line.remove(cursor);
+                    }
+                    None
+                }
+                CTRL_K => {
+                    line.truncate(cursor);
+                    None
+                }
+                CTRL_U => {
+                    line.drain(..cursor);
+                    cursor = 0;
+                    None
+                }
+                ESCAPE => read_escape(),
+                _ if byte >= 0x20 => {
+                    if let Some(c) = read_char(byte) {
+                        line.insert(cursor, c);
+                        cursor += 1;
+                    }
+                    None
+                }
+                // Any other control character does nothing
+                _ => None,
+            };
+
+            match key {
+                Some(Key::Left) => cursor = cursor.saturating_sub(1),
+                Some(Key::Right) => cursor = (cursor + 1).min(line.len()),
+                Some(Key::Home) => cursor = 0,
+                Some(Key::End) => cursor = line.len(),
+                Some(Key::Delete) if cursor < line.len() => {
+                    line.remove(cursor);
+                }
+                Some(Key::Up) if recalled > 0 => {
+                    if recalled == self.history.len() {
+                        draft = line.clone();
+                    }
+                    recalled -= 1;
+                    line = self.history[recalled].chars().collect();
+                    cursor = line.len();
+                }
+                Some(Key::Down) if recalled < self.history.len() => {
+                    recalled += 1;
+                    line = match self.history.get(recalled) {
+                        Some(entry) => entry.chars().collect(),
+                        None => draft.clone(),
+                    };
+                    cursor = line.len();
+                }
+                _ => {}
+            }
+            redraw(prompt, &line, cursor);
+        }
+    }
+}
+
+// Read a line without editing it, after showing a prompt if there is one, or return None at
+// the end of the input
+fn read_plain_line(prompt: &str) -> Option<String> {
+    if !prompt.is_empty() {
+        print!("{prompt}");
+        _ = io::stdout().flush();
+    }
+
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_string()),
+    }
+}
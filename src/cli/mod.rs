@@ -0,0 +1,310 @@
+mod batch;
+mod line_editor;
+mod repl;
+mod session;
+
+use self::session::Session;
+use crate::config::{self, BASES, OVERFLOWS};
+use crate::i18n::{gettext, gettext_with};
+use crate::logic::{format_result, ErrorKind, Format, Negatives, Options, WORD_SIZES};
+use crate::types::Int;
+
+use std::fs::File;
+use std::io::{self, BufReader};
+
+// Define the exit codes of the command line, a result, an expression that can't be read, an
+// expression that has no result, e.g. a division by zero, arguments that can't be read, a
+// file of expressions that can't be opened and one that can't be read to its end
+const EXIT_SUCCESS: i32 = 0;
+const EXIT_PARSE_ERROR: i32 = 1;
+const EXIT_DOMAIN_ERROR: i32 = 2;
+const EXIT_USAGE: i32 = 64;
+const EXIT_NO_INPUT: i32 = 66;
+const EXIT_IO_ERROR: i32 = 74;
+
+// Define the separator of digit groups in results printed, which stays the same whatever the
+// language, so that scripts can read them
+const GROUP_SEPARATOR: &str = ",";
+
+const USAGE: &str = "Usage: calculator [OPTION]... [-e] EXPRESSION
+  or:  calculator [OPTION]... --repl
+  or:  calculator [OPTION]... --batch [FILE]
+
+Print the result of an expression and exit, evaluate the expressions typed in the terminal
+or the lines of a file one after another, or open the calculator without arguments.
+
+Options:
+  -e, --expression EXPR   evaluate EXPR, which can also be given without -e
+  -r, --repl              read expressions and commands typed in the terminal, see \":help\"
+      --batch [FILE]      print the result of each line of FILE, or of the standard input
+                          without FILE or when it is \"-\", where \"#\" starts a comment
+      --fail-fast         stop a batch at the first line that has no result
+  -w, --word-size BITS    limit numbers to 8, 16 or 32 bits, 32 unless told otherwise
+  -o, --overflow MODE     saturate or wrap results that don't fit, saturate unless told otherwise
+  -b, --base BASE         print the result in decimal, hexadecimal, octal or binary
+  -g, --group-digits      group the digits of the result
+  -t, --twos-complement   print negative results in other bases in two's complement
+  -h, --help              print this help and exit
+
+A base or mode can be shortened to the start of its name, e.g. \"hex\".
+
+Exit status is 0 for a result, 1 for an expression that can't be read, 2 for an expression
+that has no result, such as a division by zero or the square root of a negative number, 64 for
+arguments that can't be read, 66 for a file that can't be opened and 74 for one that can't be
+read to its end, e.g. one that isn't UTF-8. A batch otherwise exits with the status of its
+first error.";
+
+// Define what the command line is asked to do
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Command {
+    Help,
+    Evaluate(String),
+    Repl,
+    // Evaluate the lines of a file, or of the standard input if there is none, and whether to
+    // stop at the first error
+    Batch(Option<String>, bool),
+}
+
+// Find a choice by its name in the file, or by the start of its name if no other name starts
+// the same way, e.g. "hex" for "hexadecimal"
+fn choice_named<T: Copy>(choices: &[(T, &str, &str)], name: &str) -> Option<T> {
+    config::from_name(choices, name).or_else(|| {
+        let mut found = choices
+            .iter()
+            .filter(|(_, choice, _)| !name.is_empty() && choice.starts_with(name));
+        match (found.next(), found.next()) {
+            (Some((value, _, _)), None) => Some(*value),
+            _ => None,
+        }
+    })
+}
+
+// Read the value that follows an option, e.g. "16" in "-w 16"
+fn value_of(option: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| gettext_with("\"{option}\" needs a value", &[("option", option)]))
+}
+
+// Read the arguments, the options that change how the expression is evaluated and written
+// and what to do
+fn parse_args(args: Vec<String>) -> Result<(Command, Options, Format), String> {
+    let mut options = Options::default();
+    let mut format = Format::default();
+    let mut expression: Option<String> = None;
+    let mut repl = false;
+    let mut batch = false;
+    let mut fail_fast = false;
+    let mut words: Vec<String> = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let invalid = |value: &str| {
+            gettext_with(
+                "\"{value}\" is not a value of \"{option}\"",
+                &[("value", value), ("option", &arg)],
+            )
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok((Command::Help, options, format)),
+            "-e" | "--expression" => expression = Some(value_of(&arg, &mut args)?),
+            "-r" | "--repl" => repl = true,
+            "--batch" => batch = true,
+            "--fail-fast" => fail_fast = true,
+            "-w" | "--word-size" => {
+                let value = value_of(&arg, &mut args)?;
+                options.word_size = value
+                    .parse()
+                    .ok()
+                    .filter(|bits| WORD_SIZES.contains(bits))
+                    .ok_or_else(|| invalid(&value))?;
+            }
+            "-o" | "--overflow" => {
+                let value = value_of(&arg, &mut args)?;
+                options.overflow =
+                    choice_named(&OVERFLOWS, &value).ok_or_else(|| invalid(&value))?;
+            }
+            "-b" | "--base" => {
+                let value = value_of(&arg, &mut args)?;
+                options.base = choice_named(&BASES, &value).ok_or_else(|| invalid(&value))?;
+            }
+            "-g" | "--group-digits" => format.grouping = true,
+            "-t" | "--twos-complement" => format.negatives = Negatives::TwosComplement,
+            // "--" ends the options, so that an expression can start with "-"
+            "--" => words.extend(args.by_ref()),
+            // A "-" followed by a letter is an option, followed by a digit a negative number
+            _ if arg.starts_with("--")
+                || arg.len() == 2
+                    && arg.starts_with('-')
+                    && arg[1..].chars().all(|c| c.is_ascii_alphabetic()) =>
+            {
+                return Err(gettext_with(
+                    "\"{option}\" is not an option",
+                    &[("option", &arg)],
+                ));
+            }
+            // Anything else is part of the expression, so that it can be typed without quotes
+            _ => words.push(arg),
+        }
+    }
+
+    let command =
+        match (expression, words.is_empty()) {
+            _ if repl && batch => {
+                return Err(gettext(
+                    "the REPL and a batch read expressions differently, there can't be both",
+                ))
+            }
+            _ if fail_fast && !batch => return Err(gettext("--fail-fast only applies to a batch")),
+            // A single word is the file, and "-" the standard input
+            (None, _) if batch && words.len() <= 1 => {
+                Command::Batch(words.pop().filter(|path| path != "-"), fail_fast)
+            }
+            _ if batch => return Err(gettext(
+                "a batch reads one file of expressions, there can't be more or another expression",
+            )),
+            (None, true) if repl => Command::Repl,
+            _ if repl => {
+                return Err(gettext(
+                    "the REPL reads its expressions, there can't be one in the arguments",
+                ))
+            }
+            (Some(expression), true) => Command::Evaluate(expression),
+            (None, false) => Command::Evaluate(words.join(" ")),
+            (Some(_), false) => {
+                return Err(gettext(
+                    "the expression is given with -e, there can't be another one",
+                ))
+            }
+            (None, true) => return Err(gettext("there is no expression to evaluate")),
+        };
+    Ok((command, options, format))
+}
+
+// Describe why an expression can't be evaluated, with the characters it was found at marked
+// below it, e.g. "Expected a number" over "2 + x 3" and "    ^"
+fn describe_error(text: &str, kind: ErrorKind, (start, end): (usize, usize)) -> String {
+    let marks = " ".repeat(start) + &"^".repeat(end.saturating_sub(start).max(1));
+    format!("{}\n  {text}\n  {marks}", gettext(kind.message()))
+}
+
+// Get the exit status of an expression that can't be evaluated
+fn error_status(kind: ErrorKind) -> i32 {
+    if kind.is_domain_error() {
+        EXIT_DOMAIN_ERROR
+    } else {
+        EXIT_PARSE_ERROR
+    }
+}
+
+// Write a result to print in a format
+fn write_result(value: Int, options: Options, format: Format) -> String {
+    format_result(value, options, format, GROUP_SEPARATOR)
+}
+
+// Run the command line if any arguments are given, and return the exit status, or return None
+// to open the calculator
+pub fn run(args: Vec<String>) -> Option<i32> {
+    if args.is_empty() {
+        return None;
+    }
+
+    let status = match parse_args(args) {
+        Ok((Command::Help, _, _)) => {
+            println!("{}", gettext(USAGE));
+            EXIT_SUCCESS
+        }
+        Ok((Command::Repl, options, format)) => {
+            repl::run(Session::new(options, format));
+            EXIT_SUCCESS
+        }
+        Ok((Command::Evaluate(text), options, format)) => {
+            match Session::new(options, format).evaluate(&text) {
+                Ok(value) => {
+                    println!("{}", write_result(value, options, format));
+                    EXIT_SUCCESS
+                }
+                Err((kind, range)) => {
+                    eprintln!("calculator: {}", describe_error(&text, kind, range));
+                    error_status(kind)
+                }
+            }
+        }
+        Ok((Command::Batch(None, fail_fast), options, format)) => batch::run(
+            Session::new(options, format),
+            io::stdin().lock(),
+            &mut io::stdout(),
+            &mut io::stderr(),
+            fail_fast,
+        ),
+        Ok((Command::Batch(Some(path), fail_fast), options, format)) => match File::open(&path) {
+            Ok(file) => batch::run(
+                Session::new(options, format),
+                BufReader::new(file),
+                &mut io::stdout(),
+                &mut io::stderr(),
+                fail_fast,
+            ),
+            Err(error) => {
+                eprintln!(
+                    "calculator: {}",
+                    gettext_with(
+                        "\"{path}\" can't be opened: {error}",
+                        &[("path", &path), ("error", &error.to_string())],
+                    )
+                );
+                EXIT_NO_INPUT
+            }
+        },
+        Err(message) => {
+            eprintln!(
+                "calculator: {message}\n{}",
+                gettext("Try \"calculator --help\".")
+            );
+            EXIT_USAGE
+        }
+    };
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Base;
+
+    // Run the command line with some arguments
+    fn status(args: &[&str]) -> Option<i32> {
+        run(args.iter().map(|arg| arg.to_string()).collect())
+    }
+
+    #[test]
+    fn no_arguments_open_the_calculator() {
+        assert_eq!(status(&[]), None);
+    }
+
+    #[test]
+    fn exit_status_tells_why_there_is_no_result() {
+        assert_eq!(status(&["2", "+", "2"]), Some(EXIT_SUCCESS));
+        assert_eq!(status(&["-e", "2 +"]), Some(EXIT_PARSE_ERROR));
+        assert_eq!(status(&["-e", "1 ÷ 0"]), Some(EXIT_DOMAIN_ERROR));
+        assert_eq!(status(&["√", "-4"]), Some(EXIT_DOMAIN_ERROR));
+        assert_eq!(status(&["-w", "7", "1"]), Some(EXIT_USAGE));
+        assert_eq!(status(&["--unknown", "1"]), Some(EXIT_USAGE));
+        assert_eq!(
+            status(&["--batch", "/nonexistent/expressions"]),
+            Some(EXIT_NO_INPUT)
+        );
+    }
+
+    #[test]
+    fn options_can_be_shortened_and_ended() {
+        let args = ["-b", "hex", "--", "-1"].map(String::from).to_vec();
+        let (command, options, _) = parse_args(args).unwrap();
+        assert_eq!(command, Command::Evaluate("-1".to_string()));
+        assert_eq!(options.base, Base::Hexadecimal);
+        // A value has to be a name or the start of one
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()).collect());
+        assert!(parse(&["-o", "w", "1"]).is_ok());
+        assert!(parse(&["-b", "x", "1"]).is_err());
+    }
+}
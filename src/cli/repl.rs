@@ -0,0 +1,154 @@
+use super::line_editor::LineEditor;
+use super::session::Session;
+use super::{choice_named, describe_error, write_result};
+use crate::config::{self, BASES, OVERFLOWS};
+use crate::i18n::{gettext, gettext_with};
+use crate::logic::WORD_SIZES;
+
+use crate::files::{data_file, save_lines};
+
+use std::fs;
+
+// Define the prompt lines are typed after
+const PROMPT: &str = "> ";
+
+// Define how many lines the history of the REPL keeps
+const HISTORY_LIMIT: usize = 500;
+
+const HELP: &str =
+    "Type an expression to print its result, or \"name = expression\" to set a variable.
+The result of the last expression is \"ans\".
+
+Commands:
+  :base [BASE]      print results in decimal, hexadecimal, octal or binary, e.g. \":base hex\"
+  :width [BITS]     limit numbers to 8, 16 or 32 bits
+  :mode [MODE]      saturate or wrap results that don't fit
+  :trace [on|off]   print each operation of an expression before its result
+  :vars             print the variables
+  :help             print this help
+  :quit             leave, like Ctrl-D
+
+Without a value, a command prints what it is set to.";
+
+// Define the file the lines typed in the REPL are saved to
+const HISTORY_FILE: &str = "repl-history";
+
+// Load the lines typed in earlier sessions, a missing or unreadable file means there are none
+fn load_history() -> Vec<String> {
+    fs::read_to_string(data_file(HISTORY_FILE))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+// Save the newest lines typed, one per line
+fn save_history(history: &[String]) {
+    let start = history.len().saturating_sub(HISTORY_LIMIT);
+    save_lines(&data_file(HISTORY_FILE), history[start..].iter().cloned());
+}
+
+// Run a command, e.g. ":base hex", with whether expressions are traced, and return what to
+// print, or why it can't be run
+fn run_command(
+    session: &mut Session,
+    tracing: &mut bool,
+    command: &str,
+    value: Option<&str>,
+) -> Result<String, String> {
+    let invalid = |value: &str| {
+        gettext_with(
+            "\"{value}\" is not a value of \"{option}\"",
+            &[("value", value), ("option", command)],
+        )
+    };
+    let options = &mut session.options;
+
+    match (command, value) {
+        (":base", Some(value)) => {
+            options.base = choice_named(&BASES, value).ok_or_else(|| invalid(value))?;
+        }
+        (":width", Some(value)) => {
+            options.word_size = value
+                .parse()
+                .ok()
+                .filter(|bits| WORD_SIZES.contains(bits))
+                .ok_or_else(|| invalid(value))?;
+        }
+        (":mode", Some(value)) => {
+            options.overflow = choice_named(&OVERFLOWS, value).ok_or_else(|| invalid(value))?;
+        }
+        (":trace", Some("on")) => *tracing = true,
+        (":trace", Some("off")) => *tracing = false,
+        (":trace", Some(value)) => return Err(invalid(value)),
+        (":trace", None) => *tracing = !*tracing,
+        (":vars", None) => {
+            let (options, format) = (session.options, session.format);
+            return Ok(session
+                .variables
+                .iter()
+                .map(|(name, value)| format!("{name} = {}", write_result(*value, options, format)))
+                .collect::<Vec<String>>()
+                .join("\n"));
+        }
+        (":help", None) => return Ok(gettext(HELP)),
+        (":base" | ":width" | ":mode", None) => {}
+        _ => {
+            return Err(gettext_with(
+                "\"{command}\" is not a command, see \":help\"",
+                &[("command", command)],
+            ))
+        }
+    }
+
+    // A command that sets something, or is asked what it is set to, tells what it is set to
+    let options = session.options;
+    Ok(match command {
+        ":base" => config::to_name(&BASES, options.base).to_string(),
+        ":width" => options.word_size.to_string(),
+        ":mode" => config::to_name(&OVERFLOWS, options.overflow).to_string(),
+        _ if *tracing => gettext("Tracing is on"),
+        _ => gettext("Tracing is off"),
+    })
+}
+
+// Read lines in a terminal, print the result of each expression typed, and run the commands
+// that change how they are evaluated, until the input ends or the user leaves
+pub fn run(mut session: Session) {
+    let mut editor = LineEditor::new(load_history());
+    let mut tracing = false;
+
+    while let Some(line) = editor.read_line(PROMPT) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with(':') {
+            let mut words = line.split_whitespace();
+            let command = words.next().unwrap_or_default();
+            if command == ":quit" {
+                break;
+            }
+            match run_command(&mut session, &mut tracing, command, words.next()) {
+                Ok(message) if message.is_empty() => {}
+                Ok(message) => println!("{message}"),
+                Err(message) => eprintln!("{message}"),
+            }
+            continue;
+        }
+
+        let (options, format) = (session.options, session.format);
+        if tracing {
+            for step in session.trace(line, |value| write_result(value, options, format)) {
+                println!("  {step}");
+            }
+        }
+        match session.evaluate(line) {
+            Ok(value) => println!("{}", write_result(value, options, format)),
+            Err((kind, range)) => eprintln!("{}", describe_error(line, kind, range)),
+        }
+    }
+
+    save_history(&editor.history);
+}
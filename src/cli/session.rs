@@ -0,0 +1,108 @@
+use crate::logic::{
+    calculate_value, is_variable_name, scan_with, trace, ErrorKind, Format, Options,
+};
+use crate::types::Int;
+
+use std::collections::BTreeMap;
+
+// Define the name of the variable that holds the result of the last expression
+const ANSWER: &str = "ans";
+
+// Define a session of the command line, the options expressions are evaluated with, the format
+// their results are written in, and the variables set so far
+pub struct Session {
+    pub options: Options,
+    pub format: Format,
+    pub variables: BTreeMap<String, Int>,
+}
+
+// Split an assignment to a variable, e.g. "rate = 3 x 4", into the name, the expression and the
+// position the expression starts at, or return None if the line is an expression
+// A name followed by "==" is a comparison, e.g. "rate == 12"
+fn split_assignment(line: &str) -> Option<(&str, &str, usize)> {
+    let (name, expression) = line.split_once('=')?;
+    if expression.starts_with('=') || name.trim().is_empty() {
+        return None;
+    }
+    let name = name.trim();
+    if !name.chars().all(char::is_alphabetic) {
+        return None;
+    }
+
+    let start = line.len() - expression.len();
+    Some((name, expression, line[..start].chars().count()))
+}
+
+impl Session {
+    pub fn new(options: Options, format: Format) -> Session {
+        Session {
+            options,
+            format,
+            variables: BTreeMap::new(),
+        }
+    }
+
+    // Evaluate an expression, or return why it can't be and the characters it was found at
+    fn evaluate_expression(&self, text: &str) -> Result<Int, (ErrorKind, (usize, usize))> {
+        let chars: Vec<char> = text.chars().collect();
+        let tokens = scan_with(text, &self.variables).map_err(|position| {
+            // A word that can't be read is a variable that isn't set
+            if chars[position].is_alphabetic() {
+                let end = (position..chars.len())
+                    .find(|i| !chars[*i].is_alphabetic())
+                    .unwrap_or(chars.len());
+                (ErrorKind::UnknownName, (position, end))
+            } else {
+                (ErrorKind::UnknownCharacter, (position, position + 1))
+            }
+        })?;
+        let input: Vec<String> = tokens.iter().map(|token| token.text.clone()).collect();
+
+        calculate_value(input, self.options).map_err(|error| {
+            // an error that can't be pinned on a token is the whole expression's fault
+            let range = error
+                .index
+                .and_then(|index| tokens.get(index))
+                .map_or((0, chars.len()), |token| (token.start, token.end));
+            (error.kind, range)
+        })
+    }
+
+    // Evaluate a line, an expression or an assignment of one to a variable, and keep its result
+    // as the answer, or return why it can't be and the characters of the line it was found at
+    pub fn evaluate(&mut self, line: &str) -> Result<Int, (ErrorKind, (usize, usize))> {
+        let value = match split_assignment(line) {
+            Some((name, expression, offset)) => {
+                // A name of an operator can't be set, e.g. "x = 3"
+                if !is_variable_name(name) {
+                    let start = line.chars().take_while(|c| c.is_whitespace()).count();
+                    return Err((
+                        ErrorKind::UnknownName,
+                        (start, start + name.chars().count()),
+                    ));
+                }
+                let value = self
+                    .evaluate_expression(expression)
+                    .map_err(|(kind, (start, end))| (kind, (start + offset, end + offset)))?;
+                self.variables.insert(name.to_string(), value);
+                value
+            }
+            None => self.evaluate_expression(line)?,
+        };
+
+        self.variables.insert(ANSWER.to_string(), value);
+        Ok(value)
+    }
+
+    // Describe each operation of evaluating a line in the order it is evaluated, with its
+    // numbers written by a function
+    pub fn trace(&self, line: &str, write: impl Fn(Int) -> String) -> Vec<String> {
+        let text = split_assignment(line).map_or(line, |(_, expression, _)| expression);
+        let Ok(tokens) = scan_with(text, &self.variables) else {
+            return Vec::new();
+        };
+        let input: Vec<String> = tokens.into_iter().map(|token| token.text).collect();
+
+        trace(input, self.options, write)
+    }
+}
@@ -1,27 +1,190 @@
-use super::{buttons_grid, screen_textview};
+use super::{
+    buttons_grid, history_panel, memory_panel, notation_bar, percent_label, programmer_panel,
+    screen_textview,
+};
+use crate::config::{Config, Mode};
+use crate::history::History;
+use crate::layout;
+use crate::logic::{tokenize, ErrorKind};
+use crate::memory::Memory;
+use crate::types::Int;
 
 use gtk::prelude::*;
-use gtk::Box;
-use leptos::{create_signal, Scope};
+use gtk::{Box, Label, Orientation, TextBuffer};
+use leptos::{
+    create_effect, create_memo, create_signal, Memo, ReadSignal, RwSignal, Scope, SignalSet,
+    SignalWith,
+};
 
 const MARGIN: i32 = 5;
 
-pub fn new(cx: Scope, window_width: i32) -> Box {
-    let (output, set_output) = create_signal(cx, Vec::<String>::new());
+// Define the window width from which the panels are laid out beside the calculator, and a
+// mode shows the keys of a fuller one
+const WIDE_WIDTH: i32 = 640;
 
-    let screen_textview = screen_textview::new(cx, output, window_width, MARGIN);
-    let buttons_grid = buttons_grid::new(set_output, window_width, MARGIN);
+// Define the modes that show the keys and displays of a fuller mode once the window is wide,
+// (mode, fuller mode)
+const WIDE_MODES: [(Mode, Mode); 1] = [(Mode::Basic, Mode::Scientific)];
+
+// Define the size classes of the calculator and the width it needs for each, (class, width)
+// The style scales the fonts, the buttons and the expression line with them
+const SIZE_CLASSES: [(&str, i32); 3] = [("small", 0), ("medium", 400), ("large", 520)];
+
+pub fn new(
+    cx: Scope,
+    config: Memo<Config>,
+    history: RwSignal<History>,
+    memory: RwSignal<Memory>,
+    width: ReadSignal<i32>,
+    mode: ReadSignal<Mode>,
+) -> Box {
+    let options = create_memo(cx, move |_| config.with(|config| config.options));
+    let format = create_memo(cx, move |_| config.with(|config| config.format));
+
+    let text_buffer = TextBuffer::new(None);
+    // every edit is a user action, from typing to clears and evaluations, so keep them all for undo
+    text_buffer.set_max_undo_levels(0);
+    let (tokens, set_tokens) = create_signal(cx, Vec::<String>::new());
+    let (error, set_error) = create_signal(cx, None::<ErrorKind>);
+    let (announcement, set_announcement) = create_signal(cx, None::<(String, Int)>);
+
+    // re-read the tokens of the expression whenever it is edited
+    text_buffer.connect_changed(move |text_buffer| {
+        let (start, end) = text_buffer.bounds();
+        let text = text_buffer.text(&start, &end, false);
+        set_tokens.set(tokenize(&text).unwrap_or_default());
+        set_error.set(None);
+        set_announcement.set(None);
+    });
+
+    let screen_textview = screen_textview::new(
+        cx,
+        &text_buffer,
+        tokens,
+        error,
+        announcement,
+        config,
+        MARGIN,
+    );
+    let percent_label = percent_label::new(cx, tokens, options, MARGIN);
+    let notation_bar = notation_bar::new(cx, tokens, options, MARGIN);
+    let programmer_panel = programmer_panel::new(cx, tokens, options, format, MARGIN);
+    let layout_error_label = Label::builder()
+        .wrap(true)
+        .xalign(0.0)
+        .selectable(true)
+        .margin_start(MARGIN)
+        .margin_end(MARGIN)
+        .css_classes(["hint", "error"])
+        .visible(false)
+        .build();
+    let buttons_grid = buttons_grid::new(
+        &text_buffer,
+        options,
+        history,
+        set_error,
+        set_announcement,
+        memory,
+        MARGIN,
+    );
+    let history_panel = history_panel::new(cx, history, config, &text_buffer, MARGIN);
+    let memory_panel = memory_panel::new(cx, &text_buffer, options, memory, MARGIN);
+
+    let calculator_box = gtk::Box::builder()
+        .orientation(Orientation::Vertical)
+        .hexpand(true)
+        .build();
+
+    calculator_box.append(&screen_textview);
+    calculator_box.append(&percent_label);
+    calculator_box.append(&notation_bar);
+    calculator_box.append(&programmer_panel);
+    calculator_box.append(&layout_error_label);
+    calculator_box.append(&buttons_grid);
+
+    // the mode whose keys are shown, a fuller one in a wide window if the mode has one
+    // the memo only changes when the shown mode does, not with every change of the width
+    let shown_mode = create_memo(cx, move |_| {
+        let mode = mode();
+        WIDE_MODES
+            .iter()
+            .find(|(narrow, _)| *narrow == mode && width() >= WIDE_WIDTH)
+            .map_or(mode, |(_, wide)| *wide)
+    });
+
+    // show the keys and displays of the mode, the expression and everything else stay as they are
+    // the layout is read again every time, so that changes to it show by switching modes
+    create_effect(cx, {
+        let buttons_grid = buttons_grid.clone();
+        move |_| {
+            let mode = shown_mode();
+            let (keys, error) = layout::load(mode);
+            buttons_grid::show_layout(&buttons_grid, &keys);
+            layout_error_label.set_text(error.as_deref().unwrap_or_default());
+            layout_error_label.set_visible(error.is_some());
+            notation_bar.set_visible(mode == Mode::Scientific);
+            programmer_panel.set_visible(mode == Mode::Programmer);
+        }
+    });
+
+    let panels_box = gtk::Box::new(Orientation::Vertical, MARGIN);
+    panels_box.append(&history_panel);
+    panels_box.append(&memory_panel);
 
     let vbox = gtk::Box::builder()
-        .orientation(gtk::Orientation::Vertical)
+        .orientation(Orientation::Vertical)
         .margin_top(MARGIN)
         .margin_bottom(MARGIN)
         .margin_start(MARGIN)
         .margin_end(MARGIN)
         .build();
 
-    vbox.append(&screen_textview);
-    vbox.append(&buttons_grid);
+    vbox.append(&calculator_box);
+    vbox.append(&panels_box);
+
+    // adapt the layout to the width of the window
+    create_effect(cx, {
+        let vbox = vbox.clone();
+        move |was_wide: Option<bool>| {
+            let is_wide = width() >= WIDE_WIDTH;
+
+            // lay the panels out beside the calculator when there is room for them, and show
+            // the history there, but leave opening and closing it to the user otherwise
+            if was_wide != Some(is_wide) {
+                let orientation = if is_wide {
+                    Orientation::Horizontal
+                } else {
+                    Orientation::Vertical
+                };
+                vbox.set_orientation(orientation);
+                vbox.set_homogeneous(is_wide);
+                history_panel.set_expanded(is_wide);
+            }
+
+            // size the calculator to the width it gets
+            let calculator_width = if is_wide { width() / 2 } else { width() };
+            let size_class = SIZE_CLASSES
+                .iter()
+                .rev()
+                .find(|(_, min_width)| calculator_width >= *min_width)
+                .map_or("small", |(class, _)| *class);
+            for (class, _) in SIZE_CLASSES {
+                if class == size_class {
+                    vbox.add_css_class(class);
+                } else {
+                    vbox.remove_css_class(class);
+                }
+            }
+
+            is_wide
+        }
+    });
+
+    vbox.add_controller(buttons_grid::key_controller(
+        &buttons_grid,
+        &screen_textview,
+        options,
+    ));
 
     vbox
 }
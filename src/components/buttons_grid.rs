@@ -1,128 +1,595 @@
-use crate::logic::calculate;
+use super::screen_textview::ERROR_TAG;
+use crate::history::{History, HistoryEntry};
+use crate::i18n::{gettext, localize_number};
+use crate::layout::{self, is_number_part};
+use crate::logic::{
+    calculate_value, format_number, is_single_number, scan, tokenize, Base, ErrorKind, Options,
+};
+use crate::memory::Memory;
+use crate::types::Int;
 
+use std::time::Duration;
+
+use gtk::accessible::Property;
+use gtk::gdk::{Key, ModifierType};
+use gtk::gio::{SimpleAction, SimpleActionGroup};
+use gtk::glib::{self, clone, VariantTy};
 use gtk::prelude::*;
-use gtk::{Button, Grid};
-use leptos::{SignalUpdate, WriteSignal};
+use gtk::{
+    Button, Editable, EventControllerKey, Grid, Inhibit, PropagationPhase, StateFlags, TextBuffer,
+    TextView,
+};
+use leptos::{
+    Memo, RwSignal, SignalGetUntracked, SignalSet, SignalUpdate, SignalWithUntracked, WriteSignal,
+};
 
-const GRID_COLUMNS: i32 = 4;
 const GRID_SPACING: i32 = 5;
-const MAX_OUTPUT_LENGTH: usize = 88;
-
-const BUTTONS: [(&str, i32, i32); 20] = [
-    ("CE", 0, 0),
-    ("(", 0, 1),
-    (")", 0, 2),
-    ("^", 0, 3),
-    ("7", 1, 0),
-    ("8", 1, 1),
-    ("9", 1, 2),
-    ("-", 1, 3),
-    ("4", 2, 0),
-    ("5", 2, 1),
-    ("6", 2, 2),
-    ("÷", 2, 3),
-    ("1", 3, 0),
-    ("2", 3, 1),
-    ("3", 3, 2),
-    ("x", 3, 3),
-    ("0", 4, 0),
-    ("=", 4, 1),
-    ("√", 4, 2),
-    ("+", 4, 3),
+const PRESS_DURATION: Duration = Duration::from_millis(100);
+
+// Define the names screen readers speak for keys whose labels are symbols, (label, name)
+const ACCESSIBLE_NAMES: [(&str, &str); 32] = [
+    ("CE", "clear entry"),
+    ("C", "clear"),
+    ("⌫", "backspace"),
+    ("=", "equals"),
+    ("(", "open parenthesis"),
+    (")", "close parenthesis"),
+    ("^", "power"),
+    ("√", "square root"),
+    ("÷", "divide"),
+    ("x", "multiply"),
+    ("-", "minus"),
+    ("+", "plus"),
+    ("%", "percent"),
+    ("mod", "modulo"),
+    ("MC", "memory clear"),
+    ("MR", "memory recall"),
+    ("M+", "memory add"),
+    ("M−", "memory subtract"),
+    ("<", "less than"),
+    (">", "greater than"),
+    ("<=", "less than or equal to"),
+    (">=", "greater than or equal to"),
+    ("==", "equal to"),
+    ("!=", "not equal to"),
+    ("&&", "and"),
+    ("||", "or"),
+    ("?", "then"),
+    (":", "else"),
+    ("!", "not"),
+    ("0x", "hexadecimal"),
+    ("0o", "octal"),
+    ("0b", "binary"),
 ];
 
-const ERROR_MSG: &str = "Malformed input";
+// Define the register the memory keys work on
+const MEMORY_REGISTER: &str = "M";
+
+// Define the operators typed as a symbol followed by "=", (symbol, operator)
+const EQUALS_OPERATORS: [(char, &str); 4] = [('<', "<="), ('>', ">="), ('!', "!="), ('=', "==")];
+
+// Check if a label is part of a number, such as a digit or the prefix of a base
+fn is_digit_label(label: &str) -> bool {
+    label.chars().all(|c| c.is_ascii_digit()) || is_number_part(label)
+}
+
+// Insert a label at the cursor, replacing the selection, with anything but digits set apart by spaces
+pub fn insert_label(text_buffer: &TextBuffer, label: &str) {
+    insert_spaced(text_buffer, label, !is_digit_label(label));
+}
+
+// Insert a value at the cursor, replacing the selection, set apart by spaces so that it isn't
+// read as more digits of the number before it, and negative values in parentheses so that
+// their minus isn't read as a subtraction, e.g. "2 (-5)"
+pub fn insert_value(text_buffer: &TextBuffer, value: Int) {
+    let text = if value < 0 {
+        format!("({value})")
+    } else {
+        value.to_string()
+    };
+    insert_spaced(text_buffer, &text, true);
+}
 
-fn clear_error_msg(output: &mut Vec<String>) {
-    if output.len() == 1 && output[0] == ERROR_MSG {
-        output.clear();
+// Insert text at the cursor, replacing the selection, set apart by spaces if asked
+fn insert_spaced(text_buffer: &TextBuffer, label: &str, spaced: bool) {
+    text_buffer.begin_user_action();
+    text_buffer.delete_selection(true, true);
+
+    let mut cursor = text_buffer.iter_at_mark(&text_buffer.get_insert());
+    let mut before = cursor;
+    let char_before = before.backward_char().then(|| before.char());
+    let char_after = (!cursor.is_end()).then(|| cursor.char());
+
+    let mut text = String::new();
+    if spaced && char_before.is_some_and(|c| !c.is_whitespace()) {
+        text.push(' ');
+    }
+    text.push_str(label);
+    if spaced && !char_after.is_some_and(char::is_whitespace) {
+        text.push(' ');
     }
+
+    text_buffer.insert(&mut cursor, &text);
+    text_buffer.end_user_action();
 }
 
-fn push_digit(output: &mut Vec<String>, digit: String) {
-    let len = output.len();
-    if let Some(last) = output.last() {
-        let last_last_char = last.chars().last().unwrap();
-        if len == 1 && (last_last_char == '-' || last_last_char.is_numeric()) {
-            output[len - 1].push_str(&digit);
-            return;
-        } else if len > 1 {
-            let second_last_last_char = output[len - 2].chars().last().unwrap();
-            if (!second_last_last_char.is_numeric() && last_last_char == '-')
-                || last_last_char.is_numeric()
-            {
-                output[len - 1].push_str(&digit);
+// Replace the whole expression as a single edit, which can be undone unlike setting the text
+pub fn replace_text(text_buffer: &TextBuffer, text: &str) {
+    text_buffer.begin_user_action();
+    let (mut start, mut end) = text_buffer.bounds();
+    text_buffer.delete(&mut start, &mut end);
+    text_buffer.insert(&mut start, text);
+    text_buffer.end_user_action();
+}
+
+// Evaluate the expression without changing it, if it is well formed
+pub fn current_value(text_buffer: &TextBuffer, options: Options) -> Option<Int> {
+    let (start, end) = text_buffer.bounds();
+    let tokens = tokenize(&text_buffer.text(&start, &end, false)).ok()?;
+    calculate_value(tokens, options).ok()
+}
+
+// Evaluate the expression, replacing it with its result, or else keep it and highlight the
+// characters the error was found at
+fn evaluate(
+    text_buffer: &TextBuffer,
+    options: Options,
+    history: RwSignal<History>,
+    set_error: WriteSignal<Option<ErrorKind>>,
+    set_announcement: WriteSignal<Option<(String, Int)>>,
+) {
+    let (start, end) = text_buffer.bounds();
+    let text = text_buffer.text(&start, &end, false);
+
+    let error = match scan(&text) {
+        Ok(tokens) => {
+            let input: Vec<String> = tokens.iter().map(|token| token.text.clone()).collect();
+            // there is nothing to evaluate in an empty expression
+            if input.is_empty() {
                 return;
             }
+
+            match calculate_value(input.clone(), options) {
+                Ok(value) => {
+                    let result = format_number(value, options.base);
+                    // keep every calculation in the history
+                    if !is_single_number(&input) {
+                        history.update(|history| {
+                            history.push(HistoryEntry {
+                                expression: input.join(" "),
+                                result: result.clone(),
+                            });
+                        });
+                    }
+
+                    // the tokenizer reads the minus sign of any language
+                    replace_text(text_buffer, &localize_number(&result));
+
+                    // tell what was calculated, once the expression is replaced
+                    set_announcement.set(Some((input.join(" "), value)));
+                    return;
+                }
+                // an error that can't be pinned on a token is the whole expression's fault
+                Err(error) => {
+                    let range = error
+                        .index
+                        .and_then(|index| tokens.get(index))
+                        .map_or((0, text.chars().count()), |token| (token.start, token.end));
+                    (error.kind, range)
+                }
+            }
         }
+        Err(position) => (ErrorKind::UnknownCharacter, (position, position + 1)),
+    };
+
+    let (kind, (start, end)) = error;
+    text_buffer.apply_tag_by_name(
+        ERROR_TAG,
+        &text_buffer.iter_at_offset(start as i32),
+        &text_buffer.iter_at_offset(end as i32),
+    );
+    set_error.set(Some(kind));
+}
+
+// Delete the selection, or else the token before the cursor
+fn delete_entry(text_buffer: &TextBuffer) {
+    text_buffer.begin_user_action();
+    if !text_buffer.delete_selection(true, true) {
+        let mut cursor = text_buffer.iter_at_mark(&text_buffer.get_insert());
+        let before = text_buffer.text(&text_buffer.start_iter(), &cursor, false);
+
+        // if the text before the cursor can't be read, only delete a single character
+        let start = match scan(&before) {
+            Ok(tokens) => tokens.last().map_or(0, |token| token.start as i32),
+            Err(_) => cursor.offset() - 1,
+        };
+
+        let mut start = text_buffer.iter_at_offset(start);
+        text_buffer.delete(&mut start, &mut cursor);
+    }
+    text_buffer.end_user_action();
+}
+
+// Delete the selection, or else the character before the cursor
+fn delete_char(text_buffer: &TextBuffer) {
+    text_buffer.begin_user_action();
+    if !text_buffer.delete_selection(true, true) {
+        let mut cursor = text_buffer.iter_at_mark(&text_buffer.get_insert());
+        text_buffer.backspace(&mut cursor, true, true);
     }
-    output.push(digit);
+    text_buffer.end_user_action();
+}
+
+// Check if the text before the cursor ends with a number that starts with "0x", so that the
+// letters typed after it are its digits
+fn ends_hex_number(before: &str) -> bool {
+    before
+        .trim_end_matches(|c: char| c.is_ascii_hexdigit())
+        .to_lowercase()
+        .ends_with("0x")
 }
 
-pub fn new(set_output: WriteSignal<Vec<String>>, window_width: i32, margin: i32) -> Grid {
-    let button_size = (window_width / GRID_COLUMNS) - GRID_SPACING - (margin / GRID_COLUMNS);
+// Check if the text before the cursor ends with a "0" that starts a number, so that an "x"
+// typed after it makes a hexadecimal prefix, e.g. "2 + 0" but not "10"
+fn ends_leading_zero(before: &str) -> bool {
+    before
+        .strip_suffix('0')
+        .is_some_and(|rest| !rest.ends_with(|c: char| c.is_ascii_alphanumeric()))
+}
 
+// Find the operator an "=" typed after the text before the cursor makes, if it ends with a
+// symbol that takes one, e.g. "<=" after "1 < "
+fn equals_operator(before: &str) -> Option<&'static str> {
+    let symbol = before.trim_end().chars().last()?;
+    EQUALS_OPERATORS
+        .iter()
+        .find(|(operator_symbol, _)| *operator_symbol == symbol)
+        .map(|(_, operator)| *operator)
+}
+
+// Map a key to the action of the button it presses, and the token that button inserts, given
+// the text before the cursor and the base results are written in
+// "type" inserts its token as it is and "join" replaces the symbol before the cursor with its
+// token, there are no buttons for them
+fn key_to_action(key: Key, before: &str, base: Base) -> Option<(&'static str, Option<String>)> {
+    match key {
+        Key::Return | Key::KP_Enter => return Some(("evaluate", None)),
+        Key::BackSpace => return Some(("clear-entry", None)),
+        Key::Delete | Key::KP_Delete => return Some(("clear", None)),
+        _ => {}
+    }
+
+    let token = match key.to_unicode()? {
+        // "=" after a symbol makes an operator with it, e.g. "<=", and evaluates otherwise
+        '=' => {
+            return Some(match equals_operator(before) {
+                Some(operator) => ("join", Some(operator.to_string())),
+                None => ("evaluate", None),
+            })
+        }
+        // "x" after a leading "0" is a prefix rather than a multiplication, e.g. "0xff"
+        'x' | 'X' if ends_leading_zero(before) => return Some(("type", Some("x".to_string()))),
+        // letters are digits in hexadecimal, or after the prefix of a hexadecimal number
+        c @ ('a'..='f' | 'A'..='F') if base == Base::Hexadecimal || ends_hex_number(before) => {
+            c.to_ascii_lowercase().to_string()
+        }
+        '*' => "x".to_string(),
+        '/' => "÷".to_string(),
+        character => character.to_string(),
+    };
+    Some(("insert", Some(token)))
+}
+
+// Find the button of the grid that runs an action, inserting a token for "insert"
+fn find_button(grid: &Grid, action: &str, token: Option<&str>) -> Option<Button> {
+    let action_name = format!("keys.{action}");
+
+    let mut child = grid.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if let Ok(button) = widget.downcast::<Button>() {
+            let target = button
+                .action_target_value()
+                .and_then(|target| target.get::<String>());
+            if button.action_name().as_deref() == Some(action_name.as_str())
+                && target.as_deref() == token
+            {
+                return Some(button);
+            }
+        }
+    }
+    None
+}
+
+// Press a button as if it was clicked, showing it pressed for a moment
+fn press(button: &Button) {
+    button.set_state_flags(StateFlags::ACTIVE, false);
+    button.emit_clicked();
+
+    glib::timeout_add_local_once(
+        PRESS_DURATION,
+        clone!(@weak button => move || {
+            button.unset_state_flags(StateFlags::ACTIVE);
+        }),
+    );
+}
+
+// Create a key controller that presses the grid's buttons from the keyboard
+pub fn key_controller(
+    grid: &Grid,
+    text_view: &TextView,
+    options: Memo<Options>,
+) -> EventControllerKey {
+    let controller = EventControllerKey::new();
+    // capture keys before the focused widget does, e.g. Enter on a text view means "="
+    controller.set_propagation_phase(PropagationPhase::Capture);
+
+    controller.connect_key_pressed(
+        clone!(@weak grid, @weak text_view => @default-return Inhibit(false),
+            move |_, key, _, modifiers| {
+                let focus = grid.root().and_then(|root| root.focus());
+                // leave typing to text entries, such as the history search
+                if focus.as_ref().is_some_and(|focus| focus.is::<Editable>()) {
+                    return Inhibit(false);
+                }
+                // leave pressing a button that was moved to with the keyboard to the button
+                if focus.as_ref().is_some_and(|focus| focus.is::<Button>())
+                    && matches!(key, Key::Return | Key::KP_Enter | Key::space | Key::KP_Space)
+                {
+                    return Inhibit(false);
+                }
+                // undo and redo edits of the expression, wherever the focus is
+                if modifiers.contains(ModifierType::CONTROL_MASK) && matches!(key, Key::z | Key::Z) {
+                    let text_buffer = text_view.buffer();
+                    if modifiers.contains(ModifierType::SHIFT_MASK) {
+                        text_buffer.redo();
+                    } else {
+                        text_buffer.undo();
+                    }
+                    return Inhibit(true);
+                }
+                // leave shortcuts such as Ctrl-Q to the application
+                if modifiers.intersects(ModifierType::CONTROL_MASK | ModifierType::ALT_MASK) {
+                    return Inhibit(false);
+                }
+                // leave deleting single characters to the expression line while it is being edited
+                if text_view.has_focus()
+                    && matches!(key, Key::BackSpace | Key::Delete | Key::KP_Delete)
+                {
+                    return Inhibit(false);
+                }
+
+                let text_buffer = text_view.buffer();
+                let cursor = text_buffer.iter_at_mark(&text_buffer.get_insert());
+                let before = text_buffer.text(&text_buffer.start_iter(), &cursor, false);
+                let Some((action, token)) = key_to_action(key, &before, options.get_untracked().base)
+                else {
+                    return Inhibit(false);
+                };
+
+                // a part of a number is typed even when the layout has no key for it, e.g. a
+                // hexadecimal digit in basic mode
+                if let Some(button) = find_button(&grid, action, token.as_deref()) {
+                    press(&button);
+                    Inhibit(true)
+                } else if matches!(action, "type" | "join")
+                    || token.as_deref().is_some_and(is_number_part)
+                {
+                    let token = token.map(|token| token.to_variant());
+                    _ = grid.activate_action(&format!("keys.{action}"), token.as_ref());
+                    Inhibit(true)
+                } else {
+                    Inhibit(false)
+                }
+            }
+        ),
+    );
+
+    controller
+}
+
+// Lay out the keys of a layout in the grid, replacing the keys it had
+pub fn show_layout(grid: &Grid, keys: &[layout::Key]) {
+    while let Some(child) = grid.first_child() {
+        grid.remove(&child);
+    }
+
+    for key in keys {
+        // don't take the focus from the expression line, so its cursor stays visible
+        let button = Button::builder()
+            .label(&key.label)
+            .hexpand(true)
+            .focus_on_click(false)
+            .action_name(format!("keys.{}", key.action))
+            .build();
+        if let Some(token) = &key.token {
+            button.set_action_target_value(Some(&token.to_variant()));
+        }
+        // have screen readers speak symbols as words, and the tooltip as what the key does
+        if let Some((_, name)) = ACCESSIBLE_NAMES
+            .iter()
+            .find(|(label, _)| *label == key.label)
+        {
+            button.update_property(&[Property::Label(&gettext(name))]);
+        }
+        if let Some(tooltip) = key.tooltip.as_deref().map(gettext) {
+            button.set_tooltip_text(Some(&tooltip));
+            button.update_property(&[Property::Description(&tooltip)]);
+        }
+        // tell the keys apart for the themes
+        for class in key.class.iter().flat_map(|class| class.split_whitespace()) {
+            button.add_css_class(class);
+        }
+
+        grid.attach(&button, key.column, key.row, key.width, key.height);
+    }
+}
+
+// Add an action without a parameter to a group
+fn add_action(actions: &SimpleActionGroup, name: &str, activate: impl Fn() + 'static) {
+    let action = SimpleAction::new(name, None);
+    action.connect_activate(move |_, _| activate());
+    actions.add_action(&action);
+}
+
+pub fn new(
+    text_buffer: &TextBuffer,
+    options: Memo<Options>,
+    history: RwSignal<History>,
+    set_error: WriteSignal<Option<ErrorKind>>,
+    set_announcement: WriteSignal<Option<(String, Int)>>,
+    memory: RwSignal<Memory>,
+    margin: i32,
+) -> Grid {
+    // share the width of the grid equally between the buttons, their height is set by the style
     let grid = Grid::builder()
         .column_spacing(GRID_SPACING)
         .row_spacing(GRID_SPACING)
+        .column_homogeneous(true)
+        .row_homogeneous(true)
+        .vexpand(true)
+        .css_classes(["keys"])
         .margin_top(margin)
         .margin_bottom(margin)
         .margin_start(margin)
         .margin_end(margin)
         .build();
 
-    for (label, row, col) in BUTTONS.iter() {
-        let button = Button::builder()
-            .label(*label)
-            .width_request(button_size)
-            .height_request(button_size)
-            .build();
+    // the keys of a layout only name the action they run, see layout::ACTIONS
+    let actions = SimpleActionGroup::new();
 
-        // clear entry button
-        if label == &"CE" {
-            button.connect_clicked(move |_| {
-                set_output.update(|output| {
-                    output.pop();
-                });
-            });
-        }
-        // equals button
-        else if label == &"=" {
-            button.connect_clicked(move |_| {
-                set_output.update(|output| {
-                    let result = calculate(output.to_vec(), ERROR_MSG);
-                    output.clear();
-                    output.push(result);
-                });
-            });
+    // token button
+    let insert_action = SimpleAction::new("insert", Some(VariantTy::STRING));
+    insert_action.connect_activate(clone!(@strong text_buffer => move |_, token| {
+        if let Some(token) = token.and_then(|token| token.get::<String>()) {
+            insert_label(&text_buffer, &token);
         }
-        // digit buttons
-        else if label.chars().next().unwrap().is_numeric() {
-            button.connect_clicked(move |_| {
-                set_output.update(|output| {
-                    let len = output.join("").len();
-                    if len < MAX_OUTPUT_LENGTH {
-                        clear_error_msg(output);
-                        push_digit(output, label.to_string());
-                    }
-                });
-            });
+    }));
+    actions.add_action(&insert_action);
+
+    // typed part of a number, inserted as it is
+    let type_action = SimpleAction::new("type", Some(VariantTy::STRING));
+    type_action.connect_activate(clone!(@strong text_buffer => move |_, text| {
+        if let Some(text) = text.and_then(|text| text.get::<String>()) {
+            text_buffer.begin_user_action();
+            text_buffer.delete_selection(true, true);
+            text_buffer.insert_at_cursor(&text);
+            text_buffer.end_user_action();
         }
-        // operator buttons
-        else {
-            button.connect_clicked(move |_| {
-                set_output.update(|output| {
-                    let len = output.join("").len();
-                    if len < MAX_OUTPUT_LENGTH {
-                        clear_error_msg(output);
-                        output.push(label.to_string());
-                    }
-                });
-            });
+    }));
+    actions.add_action(&type_action);
+
+    // operator that replaces the symbol before the cursor, e.g. "<" with "<="
+    let join_action = SimpleAction::new("join", Some(VariantTy::STRING));
+    join_action.connect_activate(clone!(@strong text_buffer => move |_, operator| {
+        if let Some(operator) = operator.and_then(|operator| operator.get::<String>()) {
+            text_buffer.begin_user_action();
+            text_buffer.delete_selection(true, true);
+            // the symbol goes along with the spaces it was set apart by
+            let mut end = text_buffer.iter_at_mark(&text_buffer.get_insert());
+            let mut start = end;
+            while start.backward_char() && start.char().is_whitespace() {}
+            text_buffer.delete(&mut start, &mut end);
+            insert_label(&text_buffer, &operator);
+            text_buffer.end_user_action();
         }
+    }));
+    actions.add_action(&join_action);
 
-        grid.attach(&button, *col, *row, 1, 1);
-    }
+    // clear all button
+    add_action(
+        &actions,
+        "clear",
+        clone!(@strong text_buffer => move || {
+            replace_text(&text_buffer, "");
+        }),
+    );
+    // clear entry button
+    add_action(
+        &actions,
+        "clear-entry",
+        clone!(@strong text_buffer => move || {
+            delete_entry(&text_buffer);
+        }),
+    );
+    // backspace button
+    add_action(
+        &actions,
+        "backspace",
+        clone!(@strong text_buffer => move || {
+            delete_char(&text_buffer);
+        }),
+    );
+    // equals button
+    add_action(
+        &actions,
+        "evaluate",
+        clone!(@strong text_buffer => move || {
+            evaluate(&text_buffer, options.get_untracked(), history, set_error, set_announcement);
+        }),
+    );
+    // memory clear button
+    add_action(&actions, "memory-clear", move || {
+        memory.update(|memory| memory.clear(MEMORY_REGISTER));
+    });
+    // memory recall button
+    add_action(
+        &actions,
+        "memory-recall",
+        clone!(@strong text_buffer => move || {
+            if let Some(value) = memory.with_untracked(|memory| memory.get(MEMORY_REGISTER)) {
+                insert_value(&text_buffer, value);
+            }
+        }),
+    );
+    // memory add and subtract buttons
+    add_action(
+        &actions,
+        "memory-add",
+        clone!(@strong text_buffer => move || {
+            let options = options.get_untracked();
+            if let Some(value) = current_value(&text_buffer, options) {
+                memory.update(|memory| memory.add(MEMORY_REGISTER, value, options));
+            }
+        }),
+    );
+    add_action(
+        &actions,
+        "memory-subtract",
+        clone!(@strong text_buffer => move || {
+            let options = options.get_untracked();
+            if let Some(value) = current_value(&text_buffer, options) {
+                memory.update(|memory| memory.subtract(MEMORY_REGISTER, value, options));
+            }
+        }),
+    );
+
+    grid.insert_action_group("keys", Some(&actions));
 
     grid
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equals_after_a_symbol_makes_an_operator() {
+        assert_eq!(equals_operator("1 < "), Some("<="));
+        assert_eq!(equals_operator("1 >"), Some(">="));
+        assert_eq!(equals_operator("1 ! "), Some("!="));
+        assert_eq!(equals_operator("1 ="), Some("=="));
+        assert_eq!(equals_operator("1 + 2"), None);
+        assert_eq!(equals_operator(""), None);
+    }
+
+    #[test]
+    fn hexadecimal_numbers_can_be_typed() {
+        assert!(ends_leading_zero("0"));
+        assert!(ends_leading_zero("2 + 0"));
+        assert!(!ends_leading_zero("10"));
+        assert!(ends_hex_number("2 + 0xf"));
+        assert!(ends_hex_number("0X"));
+        assert!(!ends_hex_number("2 + 10"));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::config::MODES;
+use crate::i18n::gettext;
+
+use gtk::accessible::Property;
+use gtk::gio::Menu;
+use gtk::prelude::*;
+use gtk::{Box, HeaderBar, MenuButton, Orientation, ToggleButton};
+
+pub fn new() -> HeaderBar {
+    // switch between the modes in place of the title, the toggle of the current mode is active
+    let modes_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .css_classes(["linked"])
+        .build();
+    for (_, name, label) in MODES {
+        let toggle = ToggleButton::builder()
+            .label(gettext(label))
+            .action_name("win.mode")
+            .action_target(&name.to_variant())
+            .build();
+        modes_box.append(&toggle);
+    }
+
+    let window_menu = Menu::new();
+    window_menu.append(Some(&gettext("New window")), Some("app.new-window"));
+    window_menu.append(Some(&gettext("New tab")), Some("win.new-tab"));
+
+    let history_menu = Menu::new();
+    history_menu.append(Some(&gettext("Clear history")), Some("win.clear-history"));
+
+    let preferences_menu = Menu::new();
+    preferences_menu.append(Some(&gettext("Preferences")), Some("win.preferences"));
+
+    let menu = Menu::new();
+    menu.append_section(None, &window_menu);
+    menu.append(
+        Some(&gettext("Implicit multiplication")),
+        Some("win.implicit-multiplication"),
+    );
+    menu.append_section(Some(&gettext("History")), &history_menu);
+    menu.append_section(None, &preferences_menu);
+
+    let menu_button = MenuButton::builder()
+        .icon_name("open-menu-symbolic")
+        .menu_model(&menu)
+        .build();
+    menu_button.update_property(&[Property::Label(&gettext("Main menu"))]);
+
+    let header_bar = HeaderBar::new();
+    header_bar.set_title_widget(Some(&modes_box));
+    header_bar.pack_end(&menu_button);
+
+    header_bar
+}
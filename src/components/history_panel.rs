@@ -0,0 +1,154 @@
+use super::buttons_grid::{insert_value, replace_text};
+use crate::config::Config;
+use crate::history::{History, HistoryEntry};
+use crate::i18n::{gettext, localize_result};
+use crate::logic::{single_number_value, tokenize};
+
+use gtk::accessible::Property;
+use gtk::pango::EllipsizeMode;
+use gtk::prelude::*;
+use gtk::{
+    Box, Button, Expander, Label, ListBox, Orientation, PolicyType, ScrolledWindow, SearchEntry,
+    SelectionMode, TextBuffer,
+};
+use leptos::{
+    create_effect, create_signal, Memo, RwSignal, Scope, SignalSet, SignalUpdate, SignalWith,
+};
+
+const HISTORY_HEIGHT: i32 = 150;
+
+fn new_row(
+    entry: &HistoryEntry,
+    index: usize,
+    history: RwSignal<History>,
+    config: Config,
+    text_buffer: &TextBuffer,
+) -> Box {
+    let expression_label = Label::builder()
+        .label(&entry.expression)
+        .ellipsize(EllipsizeMode::End)
+        .xalign(0.0)
+        .build();
+    let expression_button = Button::builder()
+        .child(&expression_label)
+        .tooltip_text(&entry.expression)
+        .has_frame(false)
+        .focus_on_click(false)
+        .hexpand(true)
+        .build();
+
+    // show the result that was calculated in the format of the settings, without fitting it
+    // into the word size again, so that it is the value that is recalled
+    let value = tokenize(&entry.result)
+        .ok()
+        .and_then(|tokens| single_number_value(&tokens));
+    let result = value.map_or_else(
+        || entry.result.clone(),
+        |value| localize_result(value, config.options, config.format),
+    );
+    let result_button = Button::builder()
+        .label(format!("= {result}"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+
+    let remove_button = Button::builder()
+        .icon_name("edit-delete-symbolic")
+        .tooltip_text(gettext("Remove"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+    // an icon has no name to speak
+    remove_button.update_property(&[Property::Label(&gettext("Remove from history"))]);
+
+    expression_button.connect_clicked({
+        let text_buffer = text_buffer.clone();
+        let expression = entry.expression.clone();
+        move |_| replace_text(&text_buffer, &expression)
+    });
+    // the result is set apart from the number before it like a recalled memory
+    result_button.connect_clicked({
+        let text_buffer = text_buffer.clone();
+        move |_| {
+            if let Some(value) = value {
+                insert_value(&text_buffer, value);
+            }
+        }
+    });
+    remove_button.connect_clicked(move |_| {
+        history.update(|history| {
+            history.entries.remove(index);
+        });
+    });
+
+    let row = Box::new(Orientation::Horizontal, 0);
+    row.append(&expression_button);
+    row.append(&result_button);
+    row.append(&remove_button);
+
+    row
+}
+
+pub fn new(
+    cx: Scope,
+    history: RwSignal<History>,
+    config: Memo<Config>,
+    text_buffer: &TextBuffer,
+    margin: i32,
+) -> Expander {
+    let (query, set_query) = create_signal(cx, String::new());
+
+    let search_entry = SearchEntry::builder()
+        .placeholder_text(gettext("Search expressions and results"))
+        .build();
+    search_entry.connect_search_changed(move |search_entry| {
+        set_query.set(search_entry.text().to_string());
+    });
+
+    let list_box = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .build();
+
+    let scrolled_window = ScrolledWindow::builder()
+        .hscrollbar_policy(PolicyType::Never)
+        .min_content_height(HISTORY_HEIGHT)
+        .child(&list_box)
+        .build();
+
+    let vbox = Box::new(Orientation::Vertical, margin);
+    vbox.append(&search_entry);
+    vbox.append(&scrolled_window);
+
+    let expander = Expander::builder()
+        .label(gettext("History"))
+        .margin_start(margin)
+        .margin_end(margin)
+        .css_classes(["history"])
+        .child(&vbox)
+        .build();
+
+    create_effect(cx, {
+        let text_buffer = text_buffer.clone();
+        move |_| {
+            while let Some(row) = list_box.row_at_index(0) {
+                list_box.remove(&row);
+            }
+
+            let matches: Vec<(usize, HistoryEntry)> = history.with(|history| {
+                history
+                    .search(&query())
+                    .into_iter()
+                    .map(|index| (index, history.entries[index].clone()))
+                    .collect()
+            });
+
+            // newest matching entries first
+            let config = config();
+            for (index, entry) in matches.iter().rev() {
+                list_box.append(&new_row(entry, *index, history, config, &text_buffer));
+            }
+        }
+    });
+
+    expander
+}
@@ -0,0 +1,129 @@
+use super::buttons_grid::{current_value, insert_value};
+use crate::i18n::{gettext, gettext_with, localize_number};
+use crate::logic::Options;
+use crate::memory::{Memory, REGISTERS};
+
+use gtk::accessible::Property;
+use gtk::prelude::*;
+use gtk::{Box, Button, Expander, Label, ListBox, Orientation, SelectionMode, TextBuffer};
+use leptos::{
+    create_effect, Memo, RwSignal, Scope, SignalGetUntracked, SignalUpdate, SignalWith,
+    SignalWithUntracked,
+};
+
+const NAME_WIDTH: i32 = 3;
+
+fn new_row(
+    name: &'static str,
+    value_label: &Label,
+    text_buffer: &TextBuffer,
+    options: Memo<Options>,
+    memory: RwSignal<Memory>,
+) -> Box {
+    let name_label = Label::builder()
+        .label(name)
+        .width_chars(NAME_WIDTH)
+        .xalign(0.0)
+        .build();
+
+    let store_button = Button::builder()
+        .label(gettext("STO"))
+        .tooltip_text(gettext("Store the value of the expression"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+
+    let recall_button = Button::builder()
+        .label(gettext("RCL"))
+        .tooltip_text(gettext("Insert the stored value"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+
+    let clear_button = Button::builder()
+        .icon_name("edit-clear-symbolic")
+        .tooltip_text(gettext("Clear"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+
+    // name the register the buttons of the row work on, since each row has the same buttons
+    store_button.update_property(&[Property::Label(&gettext_with(
+        "Store in {name}",
+        &[("name", name)],
+    ))]);
+    recall_button.update_property(&[Property::Label(&gettext_with(
+        "Recall {name}",
+        &[("name", name)],
+    ))]);
+    clear_button.update_property(&[Property::Label(&gettext_with(
+        "Clear {name}",
+        &[("name", name)],
+    ))]);
+
+    store_button.connect_clicked({
+        let text_buffer = text_buffer.clone();
+        move |_| {
+            if let Some(value) = current_value(&text_buffer, options.get_untracked()) {
+                memory.update(|memory| memory.store(name, value));
+            }
+        }
+    });
+    recall_button.connect_clicked({
+        let text_buffer = text_buffer.clone();
+        move |_| {
+            if let Some(value) = memory.with_untracked(|memory| memory.get(name)) {
+                insert_value(&text_buffer, value);
+            }
+        }
+    });
+    clear_button.connect_clicked(move |_| {
+        memory.update(|memory| memory.clear(name));
+    });
+
+    let row = Box::new(Orientation::Horizontal, 0);
+    row.append(&name_label);
+    row.append(value_label);
+    row.append(&store_button);
+    row.append(&recall_button);
+    row.append(&clear_button);
+
+    row
+}
+
+pub fn new(
+    cx: Scope,
+    text_buffer: &TextBuffer,
+    options: Memo<Options>,
+    memory: RwSignal<Memory>,
+    margin: i32,
+) -> Expander {
+    let list_box = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .build();
+
+    for name in REGISTERS {
+        let value_label = Label::builder()
+            .hexpand(true)
+            .xalign(0.0)
+            .selectable(true)
+            .build();
+
+        list_box.append(&new_row(name, &value_label, text_buffer, options, memory));
+
+        create_effect(cx, move |_| {
+            let value = memory.with(|memory| memory.get(name));
+            value_label.set_label(
+                &value.map_or(String::new(), |value| localize_number(&value.to_string())),
+            );
+        });
+    }
+
+    Expander::builder()
+        .label(gettext("Memory"))
+        .margin_start(margin)
+        .margin_end(margin)
+        .css_classes(["memory"])
+        .child(&list_box)
+        .build()
+}
@@ -1,3 +1,11 @@
 pub mod body_box;
 mod buttons_grid;
+pub mod header_bar;
+mod history_panel;
+mod memory_panel;
+mod notation_bar;
+mod percent_label;
+pub mod preferences_window;
+mod programmer_panel;
 mod screen_textview;
+pub mod tab_label;
@@ -0,0 +1,71 @@
+use crate::i18n::gettext;
+use crate::logic::{convert, Notation, Options};
+
+use gtk::pango::WrapMode;
+use gtk::prelude::*;
+use gtk::{Box, Label, Orientation, ToggleButton};
+use leptos::{create_effect, create_signal, Memo, ReadSignal, Scope, SignalSet};
+
+const NOTATIONS: [(&str, Notation); 3] = [
+    ("Infix", Notation::Infix),
+    ("RPN", Notation::Postfix),
+    ("Polish", Notation::Prefix),
+];
+
+pub fn new(cx: Scope, tokens: ReadSignal<Vec<String>>, options: Memo<Options>, margin: i32) -> Box {
+    let (notation, set_notation) = create_signal(cx, Notation::Infix);
+
+    let toggles_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .homogeneous(true)
+        .css_classes(["linked"])
+        .build();
+
+    let mut group: Option<ToggleButton> = None;
+    for (label, value) in NOTATIONS {
+        let toggle = ToggleButton::builder()
+            .label(gettext(label))
+            .active(value == Notation::Infix)
+            .build();
+        toggle.set_group(group.as_ref());
+
+        toggle.connect_toggled(move |toggle| {
+            if toggle.is_active() {
+                set_notation.set(value);
+            }
+        });
+
+        toggles_box.append(&toggle);
+        group.get_or_insert(toggle);
+    }
+
+    // selectable, so the converted expression can be copied
+    let expression_label = Label::builder()
+        .selectable(true)
+        .wrap(true)
+        .wrap_mode(WrapMode::Char)
+        .xalign(0.0)
+        .css_classes(["notation"])
+        .build();
+
+    let vbox = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(margin)
+        .margin_start(margin)
+        .margin_end(margin)
+        .build();
+
+    vbox.append(&toggles_box);
+    vbox.append(&expression_label);
+
+    create_effect(cx, {
+        move |_| {
+            let text = convert(tokens(), Notation::Infix, notation(), options())
+                .map(|tokens| tokens.join(" "))
+                .unwrap_or_default();
+            expression_label.set_text(&text);
+        }
+    });
+
+    vbox
+}
@@ -0,0 +1,40 @@
+use crate::i18n::{gettext_with, localize_number};
+use crate::logic::{explain_percentages, Options};
+
+use gtk::prelude::*;
+use gtk::Label;
+use leptos::{create_effect, Memo, ReadSignal, Scope};
+
+pub fn new(
+    cx: Scope,
+    tokens: ReadSignal<Vec<String>>,
+    options: Memo<Options>,
+    margin: i32,
+) -> Label {
+    let label = Label::builder()
+        .wrap(true)
+        .xalign(0.0)
+        .margin_start(margin)
+        .margin_end(margin)
+        .css_classes(["hint"])
+        .visible(false)
+        .build();
+
+    // show how each percentage in the expression is applied, e.g. "200 + 10% = 200 + 20"
+    create_effect(cx, {
+        let label = label.clone();
+        move |_| {
+            let notes: Vec<String> = explain_percentages(tokens(), options())
+                .into_iter()
+                .map(|(template, values)| {
+                    let [a, b, part] = values.map(|value| localize_number(&value.to_string()));
+                    gettext_with(template, &[("a", &a), ("b", &b), ("part", &part)])
+                })
+                .collect();
+            label.set_text(&notes.join("\n"));
+            label.set_visible(!notes.is_empty());
+        }
+    });
+
+    label
+}
@@ -0,0 +1,192 @@
+use crate::config::{Config, BASES, HEX_GROUPINGS, MIN_MAX_LENGTH, NEGATIVES, OVERFLOWS, THEMES};
+use crate::i18n::{gettext, gettext_with};
+use crate::logic::{Options, WORD_SIZES};
+
+use gtk::prelude::*;
+use gtk::{Align, DropDown, Grid, Label, SpinButton, Switch, Widget, Window};
+use leptos::{RwSignal, SignalGetUntracked, SignalUpdate};
+
+const WINDOW_TITLE: &str = "Preferences";
+const MARGIN: i32 = 10;
+
+// Define the values the history retention and the expression length can take, (min, max, step)
+const HISTORY_LIMIT_RANGE: (f64, f64, f64) = (0.0, 10000.0, 10.0);
+const MAX_LENGTH_RANGE: (f64, f64, f64) = (MIN_MAX_LENGTH as f64, 1000.0, 1.0);
+
+// Create a drop-down of labels that tells which one was selected by its index
+fn drop_down(labels: &[String], selected: usize, on_select: impl Fn(usize) + 'static) -> DropDown {
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let drop_down = DropDown::from_strings(&labels);
+    drop_down.set_selected(selected as u32);
+    drop_down.connect_selected_notify(move |drop_down| on_select(drop_down.selected() as usize));
+    drop_down
+}
+
+// Create a switch that tells when it is turned on or off
+fn switch(active: bool, on_toggle: impl Fn(bool) + 'static) -> Switch {
+    let switch = Switch::builder()
+        .active(active)
+        .halign(Align::Start)
+        .build();
+    switch.connect_active_notify(move |switch| on_toggle(switch.is_active()));
+    switch
+}
+
+// Create a spin button for a whole number that tells when it is changed
+fn spin_button(
+    (min, max, step): (f64, f64, f64),
+    value: usize,
+    on_change: impl Fn(usize) + 'static,
+) -> SpinButton {
+    let spin_button = SpinButton::with_range(min, max, step);
+    spin_button.set_value(value as f64);
+    spin_button.connect_value_changed(move |spin_button| {
+        on_change(spin_button.value_as_int().max(0) as usize);
+    });
+    spin_button
+}
+
+// Find the position of a value in a list of choices, or the first if it isn't there
+fn position<T: PartialEq>(choices: &[(T, &str, &str)], value: T) -> usize {
+    choices
+        .iter()
+        .position(|(choice, _, _)| *choice == value)
+        .unwrap_or(0)
+}
+
+// Get the translated labels of a list of choices
+fn labels<T>(choices: &[(T, &str, &str)]) -> Vec<String> {
+    choices.iter().map(|(_, _, label)| gettext(label)).collect()
+}
+
+// Show the settings, those that change how expressions are evaluated for the tab they are
+// opened from, the others for every window
+pub fn new(
+    config: RwSignal<Config>,
+    options: RwSignal<Options>,
+    parent: &impl IsA<Window>,
+) -> Window {
+    let current = Config {
+        options: options.get_untracked(),
+        ..config.get_untracked()
+    };
+
+    let word_size_labels: Vec<String> = WORD_SIZES
+        .iter()
+        .map(|bits| gettext_with("{bits} bits", &[("bits", &bits.to_string())]))
+        .collect();
+
+    // every setting applies as soon as it is changed, so there is nothing to confirm
+    let rows: [(&str, Widget); 10] = [
+        (
+            "Word size",
+            drop_down(
+                &word_size_labels,
+                WORD_SIZES
+                    .iter()
+                    .position(|bits| *bits == current.options.word_size)
+                    .unwrap_or(0),
+                move |index| options.update(|options| options.word_size = WORD_SIZES[index]),
+            )
+            .upcast(),
+        ),
+        (
+            "Overflow",
+            drop_down(
+                &labels(&OVERFLOWS),
+                position(&OVERFLOWS, current.options.overflow),
+                move |index| options.update(|options| options.overflow = OVERFLOWS[index].0),
+            )
+            .upcast(),
+        ),
+        (
+            "Results in",
+            drop_down(
+                &labels(&BASES),
+                position(&BASES, current.options.base),
+                move |index| options.update(|options| options.base = BASES[index].0),
+            )
+            .upcast(),
+        ),
+        (
+            "Implicit multiplication",
+            switch(current.options.implicit_multiplication, move |enabled| {
+                options.update(|options| options.implicit_multiplication = enabled)
+            })
+            .upcast(),
+        ),
+        (
+            "Digit grouping",
+            switch(current.format.grouping, move |enabled| {
+                config.update(|config| config.format.grouping = enabled)
+            })
+            .upcast(),
+        ),
+        (
+            "Hexadecimal groups",
+            drop_down(
+                &labels(&HEX_GROUPINGS),
+                position(&HEX_GROUPINGS, current.format.hex_grouping),
+                move |index| {
+                    config.update(|config| config.format.hex_grouping = HEX_GROUPINGS[index].0)
+                },
+            )
+            .upcast(),
+        ),
+        (
+            "Negative numbers",
+            drop_down(
+                &labels(&NEGATIVES),
+                position(&NEGATIVES, current.format.negatives),
+                move |index| config.update(|config| config.format.negatives = NEGATIVES[index].0),
+            )
+            .upcast(),
+        ),
+        (
+            "Theme",
+            drop_down(
+                &labels(&THEMES),
+                position(&THEMES, current.theme),
+                move |index| config.update(|config| config.theme = THEMES[index].0),
+            )
+            .upcast(),
+        ),
+        (
+            "History entries kept",
+            spin_button(HISTORY_LIMIT_RANGE, current.history_limit, move |limit| {
+                config.update(|config| config.history_limit = limit)
+            })
+            .upcast(),
+        ),
+        (
+            "Longest expression",
+            spin_button(MAX_LENGTH_RANGE, current.max_length, move |length| {
+                config.update(|config| config.max_length = length)
+            })
+            .upcast(),
+        ),
+    ];
+
+    let grid = Grid::builder()
+        .column_spacing(MARGIN)
+        .row_spacing(MARGIN)
+        .margin_top(MARGIN)
+        .margin_bottom(MARGIN)
+        .margin_start(MARGIN)
+        .margin_end(MARGIN)
+        .build();
+
+    for (row, (label, widget)) in rows.iter().enumerate() {
+        let label = Label::builder().label(gettext(label)).xalign(0.0).build();
+        grid.attach(&label, 0, row as i32, 1, 1);
+        grid.attach(widget, 1, row as i32, 1, 1);
+    }
+
+    Window::builder()
+        .title(gettext(WINDOW_TITLE))
+        .transient_for(parent)
+        .modal(true)
+        .resizable(false)
+        .child(&grid)
+        .build()
+}
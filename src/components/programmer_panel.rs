@@ -0,0 +1,110 @@
+use crate::i18n::{gettext, localize_result};
+use crate::logic::{preview_value, Base, Format, Options};
+use crate::types::Int;
+
+use gtk::prelude::*;
+use gtk::{Grid, Label};
+use leptos::{create_effect, Memo, ReadSignal, Scope};
+
+// Define the bases the value is shown in, (base, label)
+const BASES: [(Base, &str); 4] = [
+    (Base::Hexadecimal, "HEX"),
+    (Base::Decimal, "DEC"),
+    (Base::Octal, "OCT"),
+    (Base::Binary, "BIN"),
+];
+
+// Define how many bits each row of the bit grid shows, and how many make a group in it
+const BITS_PER_ROW: u32 = 16;
+const BITS_PER_GROUP: u32 = 4;
+
+// Write the bits of a row of the bit grid, highest first, e.g. "0000 0000 1111 1111"
+fn row_bits(value: Int, highest: u32, lowest: u32) -> String {
+    let bits: Vec<char> = (lowest..=highest)
+        .rev()
+        .map(|bit| if (value >> bit) & 1 == 1 { '1' } else { '0' })
+        .collect();
+
+    // Split the bits into groups counted from the lowest bit
+    let groups: Vec<String> = bits
+        .rchunks(BITS_PER_GROUP as usize)
+        .rev()
+        .map(|group| group.iter().collect())
+        .collect();
+    groups.join(" ")
+}
+
+pub fn new(
+    cx: Scope,
+    tokens: ReadSignal<Vec<String>>,
+    options: Memo<Options>,
+    format: Memo<Format>,
+    margin: i32,
+) -> Grid {
+    let grid = Grid::builder()
+        .column_spacing(margin)
+        .margin_start(margin)
+        .margin_end(margin)
+        .css_classes(["programmer"])
+        .build();
+
+    let value_labels: Vec<Label> = BASES
+        .iter()
+        .enumerate()
+        .map(|(row, (_, name))| {
+            let name_label = Label::builder().label(gettext(name)).xalign(0.0).build();
+            let value_label = Label::builder()
+                .xalign(1.0)
+                .hexpand(true)
+                .selectable(true)
+                .build();
+            grid.attach(&name_label, 0, row as i32, 1, 1);
+            grid.attach(&value_label, 1, row as i32, 1, 1);
+            value_label
+        })
+        .collect();
+
+    // the bit grid is as long as the word, so it is laid out again whenever the word size changes
+    let bits_grid = Grid::builder()
+        .column_spacing(margin)
+        .css_classes(["bits"])
+        .build();
+    grid.attach(&bits_grid, 0, BASES.len() as i32, 2, 1);
+
+    // show the value of the expression while it is typed, or zero while there is none
+    create_effect(cx, move |_| {
+        let (options, format) = (options(), format());
+        let value = preview_value(tokens(), options).unwrap_or(0);
+
+        for ((base, _), value_label) in BASES.iter().zip(&value_labels) {
+            let options = Options {
+                base: *base,
+                ..options
+            };
+            value_label.set_text(&localize_result(value, options, format));
+        }
+
+        while let Some(child) = bits_grid.first_child() {
+            bits_grid.remove(&child);
+        }
+        // a row per BITS_PER_ROW bits, highest first, each with the positions of its bits
+        let rows = options.word_size.div_ceil(BITS_PER_ROW);
+        for row in 0..rows {
+            let highest = options.word_size - 1 - row * BITS_PER_ROW;
+            let lowest = highest + 1 - BITS_PER_ROW.min(highest + 1);
+
+            let highest_label = Label::builder().label(highest.to_string()).build();
+            let bits_label = Label::builder()
+                .label(row_bits(value, highest, lowest))
+                .hexpand(true)
+                .build();
+            let lowest_label = Label::builder().label(lowest.to_string()).build();
+
+            bits_grid.attach(&highest_label, 0, row as i32, 1, 1);
+            bits_grid.attach(&bits_label, 1, row as i32, 1, 1);
+            bits_grid.attach(&lowest_label, 2, row as i32, 1, 1);
+        }
+    });
+
+    grid
+}
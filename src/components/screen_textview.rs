@@ -1,29 +1,209 @@
+use super::buttons_grid::{current_value, insert_label};
+use crate::config::Config;
+use crate::i18n::{gettext, group_separator, localize_result};
+use crate::logic::{
+    format_number, format_result, preview, tokenize, Base, ErrorKind, Format, Options,
+};
+use crate::types::Int;
+
+use gtk::accessible::Property;
+use gtk::gio::{Cancellable, Menu, SimpleAction, SimpleActionGroup};
+use gtk::glib::{self, clone, VariantTy};
+use gtk::pango::Underline;
 use gtk::prelude::*;
-use gtk::{TextBuffer, TextView, WrapMode};
-use leptos::{create_effect, ReadSignal, Scope};
+use gtk::{AccessibleRole, Label, TextBuffer, TextTag, TextView, TextWindowType, WrapMode};
+use leptos::{create_effect, Memo, ReadSignal, Scope, SignalWith, SignalWithUntracked};
+
+pub const ERROR_TAG: &str = "error";
+
+// Define the ways the result can be copied, (format, menu label)
+const COPY_FORMATS: [(&str, &str); 5] = [
+    ("decimal", "Copy result as decimal"),
+    ("hexadecimal", "Copy result as hexadecimal"),
+    ("octal", "Copy result as octal"),
+    ("binary", "Copy result as binary"),
+    ("grouped", "Copy result with digit grouping"),
+];
+
+// Insert pasted text at the cursor the way the tokenizer reads it, or else as it is with
+// the part that can't be read highlighted
+fn paste(text_buffer: &TextBuffer, text: &str) {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if text.is_empty() {
+        return;
+    }
+
+    match tokenize(&text) {
+        Ok(tokens) => insert_label(text_buffer, &tokens.join(" ")),
+        Err(position) => {
+            text_buffer.begin_user_action();
+            text_buffer.delete_selection(true, true);
+
+            let offset = text_buffer.iter_at_mark(&text_buffer.get_insert()).offset();
+            text_buffer.insert_at_cursor(&text);
+
+            // highlight from the first character that can't be read to the end of its word
+            let end = text
+                .chars()
+                .skip(position)
+                .position(char::is_whitespace)
+                .map_or(text.chars().count(), |length| position + length);
+            text_buffer.apply_tag_by_name(
+                ERROR_TAG,
+                &text_buffer.iter_at_offset(offset + position as i32),
+                &text_buffer.iter_at_offset(offset + end as i32),
+            );
+            text_buffer.end_user_action();
+        }
+    }
+}
 
-pub fn new(cx: Scope, output: ReadSignal<Vec<String>>, window_width: i32, margin: i32) -> TextView {
-    let text_buffer = TextBuffer::new(None);
-    text_buffer.set_text("");
+// Copy the result of the expression in one of the copy formats
+fn copy_result(text_view: &TextView, options: Options, format: &str) {
+    if let Some(value) = current_value(&text_view.buffer(), options) {
+        let text = match format {
+            "hexadecimal" => format_number(value, Base::Hexadecimal),
+            "octal" => format_number(value, Base::Octal),
+            "binary" => format_number(value, Base::Binary),
+            "grouped" => {
+                let options = Options {
+                    base: Base::Decimal,
+                    ..options
+                };
+                let format = Format {
+                    grouping: true,
+                    ..Format::default()
+                };
+                format_result(value, options, format, group_separator())
+            }
+            _ => format_number(value, Base::Decimal),
+        };
+        text_view.clipboard().set_text(&text);
+    }
+}
 
-    let text_view_height: i32 = (window_width as f64 / 2.75) as i32 - (margin * 2);
+pub fn new(
+    cx: Scope,
+    text_buffer: &TextBuffer,
+    tokens: ReadSignal<Vec<String>>,
+    error: ReadSignal<Option<ErrorKind>>,
+    announcement: ReadSignal<Option<(String, Int)>>,
+    config: Memo<Config>,
+    margin: i32,
+) -> TextView {
+    // the height of the text view is set by the style, so that it scales with the window
     let text_view = TextView::new();
-    text_view.set_height_request(text_view_height);
     text_view.set_margin_top(margin);
     text_view.set_margin_bottom(margin);
     text_view.set_margin_start(margin);
     text_view.set_margin_end(margin);
-    text_view.set_editable(false);
-    text_view.set_cursor_visible(false);
     text_view.set_monospace(true);
     text_view.set_wrap_mode(WrapMode::Char);
-    text_view.set_buffer(Some(&text_buffer));
+    text_view.set_buffer(Some(text_buffer));
+    // move the focus on with Tab, so that the keys can be reached from the keyboard
+    text_view.set_accepts_tab(false);
+    text_view.update_property(&[Property::Label(&gettext("Expression"))]);
+
+    let error_tag = TextTag::builder()
+        .name(ERROR_TAG)
+        .underline(Underline::Error)
+        .build();
+    text_buffer.tag_table().add(&error_tag);
+
+    // keep the expression from growing past the maximum length, however it is edited
+    text_buffer.connect_insert_text(move |text_buffer, _, text| {
+        let max_length = config.with_untracked(|config| config.max_length);
+        if (text_buffer.char_count() as usize) + text.chars().count() > max_length {
+            text_buffer.stop_signal_emission_by_name("insert-text");
+        }
+    });
 
-    create_effect(cx, {
-        move |_| {
-            let text = output().join(" ");
-            text_buffer.set_text(&text);
+    // stop highlighting errors once the expression is edited
+    text_buffer.connect_changed(|text_buffer| {
+        let (start, end) = text_buffer.bounds();
+        text_buffer.remove_tag_by_name(ERROR_TAG, &start, &end);
+    });
+
+    // read pasted text through the tokenizer instead of inserting it as it is
+    text_view.connect_paste_clipboard(|text_view| {
+        text_view.stop_signal_emission_by_name("paste-clipboard");
+
+        let text_buffer = text_view.buffer();
+        text_view
+            .clipboard()
+            .read_text_async(None::<&Cancellable>, move |text| {
+                if let Ok(Some(text)) = text {
+                    paste(&text_buffer, &text);
+                }
+            });
+    });
+
+    // copy the result of the expression when nothing is selected
+    text_view.connect_copy_clipboard(move |text_view| {
+        if !text_view.buffer().has_selection() {
+            text_view.stop_signal_emission_by_name("copy-clipboard");
+            let options = config.with_untracked(|config| config.options);
+            copy_result(text_view, options, "decimal");
+        }
+    });
+
+    // offer the copy formats in the context menu
+    let copy_menu = Menu::new();
+    for (format, label) in COPY_FORMATS {
+        copy_menu.append(
+            Some(&gettext(label)),
+            Some(&format!("display.copy-as::{format}")),
+        );
+    }
+    text_view.set_extra_menu(Some(&copy_menu));
+
+    let copy_action = SimpleAction::new("copy-as", Some(VariantTy::STRING));
+    copy_action.connect_activate(clone!(@weak text_view => move |_, format| {
+        if let Some(format) = format.and_then(|format| format.get::<String>()) {
+            let options = config.with_untracked(|config| config.options);
+            copy_result(&text_view, options, &format);
         }
+    }));
+
+    let actions = SimpleActionGroup::new();
+    actions.add_action(&copy_action);
+    text_view.insert_action_group("display", Some(&actions));
+
+    // show the result of the expression on a line below it while it is typed, or why it
+    // couldn't be evaluated or what was calculated until it is edited
+    // the line is a status, so screen readers announce it whenever it changes
+    let preview_label = Label::builder()
+        .xalign(1.0)
+        .selectable(true)
+        .css_classes(["preview"])
+        .accessible_role(AccessibleRole::Status)
+        .visible(false)
+        .build();
+    text_view.set_gutter(TextWindowType::Bottom, Some(&preview_label));
+
+    create_effect(cx, move |_| {
+        let (options, format) = config.with(|config| (config.options, config.format));
+        let text = match error() {
+            Some(kind) => {
+                preview_label.add_css_class("error");
+                Some(gettext(kind.message()))
+            }
+            None => {
+                preview_label.remove_css_class("error");
+                preview(tokens(), options)
+                    .map(|value| localize_result(value, options, format))
+                    .or(announcement().map(|(expression, value)| {
+                        format!("{expression} = {}", localize_result(value, options, format))
+                    }))
+            }
+        };
+        preview_label.set_text(text.as_deref().unwrap_or_default());
+        preview_label.set_visible(text.is_some());
+    });
+
+    // have the cursor in the expression whenever the calculator is shown
+    text_view.connect_map(|text_view| {
+        text_view.grab_focus();
     });
 
     text_view
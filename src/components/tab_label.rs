@@ -0,0 +1,40 @@
+use crate::config::{Mode, MODES};
+use crate::i18n::gettext;
+
+use gtk::accessible::Property;
+use gtk::prelude::*;
+use gtk::{Box, Button, Label, Orientation};
+use leptos::{create_effect, RwSignal, Scope};
+
+pub fn new(cx: Scope, mode: RwSignal<Mode>, on_close: impl Fn() + 'static) -> Box {
+    let label = Label::new(None);
+
+    let close_button = Button::builder()
+        .icon_name("window-close-symbolic")
+        .tooltip_text(gettext("Close tab"))
+        .has_frame(false)
+        .focus_on_click(false)
+        .build();
+    // an icon has no name to speak
+    close_button.update_property(&[Property::Label(&gettext("Close tab"))]);
+    close_button.connect_clicked(move |_| on_close());
+
+    // name the tab after its mode, so that tabs doing different work can be told apart
+    create_effect(cx, {
+        let label = label.clone();
+        move |_| {
+            let mode = mode();
+            let name = MODES
+                .iter()
+                .find(|(choice, _, _)| *choice == mode)
+                .map_or("", |(_, _, label)| *label);
+            label.set_text(&gettext(name));
+        }
+    });
+
+    let tab_box = Box::new(Orientation::Horizontal, 0);
+    tab_box.append(&label);
+    tab_box.append(&close_button);
+
+    tab_box
+}
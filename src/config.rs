@@ -0,0 +1,224 @@
+use crate::files::{config_file, save_lines};
+use crate::history;
+use crate::logic::{Base, Format, HexGrouping, Negatives, Options, Overflow, WORD_SIZES};
+use crate::theme::Theme;
+
+use std::fs;
+
+// Define how long an expression can get unless told otherwise
+pub const DEFAULT_MAX_LENGTH: usize = 88;
+// Define the shortest an expression can be limited to, which fits the longest result it can be
+// replaced with, "-0b" followed by 32 binary digits
+pub const MIN_MAX_LENGTH: usize = 35;
+
+// Define the modes of the calculator, each with its own keys and displays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Basic,
+    // Adds comparison and logical keys, and the notation of the expression
+    Scientific,
+    // Adds hexadecimal digits and base prefixes, and the result in every base and its bits
+    Programmer,
+}
+
+// Define the choices of the settings that have a few, (value, name in the file, label)
+pub const MODES: [(Mode, &str, &str); 3] = [
+    (Mode::Basic, "basic", "Basic"),
+    (Mode::Scientific, "scientific", "Scientific"),
+    (Mode::Programmer, "programmer", "Programmer"),
+];
+pub const OVERFLOWS: [(Overflow, &str, &str); 2] = [
+    (Overflow::Saturate, "saturate", "Saturate"),
+    (Overflow::Wrap, "wrap", "Wrap around"),
+];
+pub const BASES: [(Base, &str, &str); 4] = [
+    (Base::Decimal, "decimal", "Decimal"),
+    (Base::Hexadecimal, "hexadecimal", "Hexadecimal"),
+    (Base::Octal, "octal", "Octal"),
+    (Base::Binary, "binary", "Binary"),
+];
+pub const HEX_GROUPINGS: [(HexGrouping, &str, &str); 3] = [
+    (HexGrouping::Nibbles, "nibbles", "Nibbles"),
+    (HexGrouping::Bytes, "bytes", "Bytes"),
+    (HexGrouping::Words, "words", "16-bit words"),
+];
+pub const NEGATIVES: [(Negatives, &str, &str); 2] = [
+    (
+        Negatives::SignMagnitude,
+        "sign-magnitude",
+        "With a minus sign",
+    ),
+    (
+        Negatives::TwosComplement,
+        "twos-complement",
+        "Two's complement",
+    ),
+];
+pub const THEMES: [(Theme, &str, &str); 4] = [
+    (Theme::System, "system", "System"),
+    (Theme::Light, "light", "Light"),
+    (Theme::Dark, "dark", "Dark"),
+    (Theme::HighContrast, "high-contrast", "High contrast"),
+];
+
+// Define the settings of the calculator, which apply to every window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    // How expressions are evaluated and their results written
+    pub options: Options,
+    pub theme: Theme,
+    // How the results shown are written, e.g. "1,234,567" with the digits grouped
+    pub format: Format,
+    // How many entries the history keeps
+    pub history_limit: usize,
+    // How many characters an expression can have
+    pub max_length: usize,
+    // The mode new windows open in, the last one picked
+    pub mode: Mode,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            options: Options::default(),
+            theme: Theme::System,
+            format: Format::default(),
+            history_limit: history::DEFAULT_LIMIT,
+            max_length: DEFAULT_MAX_LENGTH,
+            mode: Mode::Basic,
+        }
+    }
+}
+
+// Define the file the settings are saved to
+const CONFIG_FILE: &str = "config";
+
+// Find the value of a choice by its name in the file
+pub fn from_name<T: Copy>(choices: &[(T, &str, &str)], name: &str) -> Option<T> {
+    choices
+        .iter()
+        .find(|(_, choice, _)| *choice == name)
+        .map(|(value, _, _)| *value)
+}
+
+// Find the name in the file of a choice
+pub fn to_name<T: PartialEq>(choices: &[(T, &'static str, &str)], value: T) -> &'static str {
+    choices
+        .iter()
+        .find(|(choice, _, _)| *choice == value)
+        .map_or("", |(_, name, _)| *name)
+}
+
+impl Config {
+    // Load the settings saved by an earlier session
+    pub fn load() -> Config {
+        let mut config = Config::default();
+
+        // A missing or unreadable file just means the defaults, and so does a missing or
+        // unreadable setting
+        for (key, value) in fs::read_to_string(config_file(CONFIG_FILE))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim(), value.trim()))
+        {
+            match key {
+                "word-size" => {
+                    if let Some(word_size) = value.parse().ok().filter(|w| WORD_SIZES.contains(w)) {
+                        config.options.word_size = word_size;
+                    }
+                }
+                "overflow" => {
+                    if let Some(overflow) = from_name(&OVERFLOWS, value) {
+                        config.options.overflow = overflow;
+                    }
+                }
+                "base" => {
+                    if let Some(base) = from_name(&BASES, value) {
+                        config.options.base = base;
+                    }
+                }
+                "implicit-multiplication" => {
+                    if let Ok(enabled) = value.parse() {
+                        config.options.implicit_multiplication = enabled;
+                    }
+                }
+                "theme" => {
+                    if let Some(theme) = from_name(&THEMES, value) {
+                        config.theme = theme;
+                    }
+                }
+                "digit-grouping" => {
+                    if let Ok(enabled) = value.parse() {
+                        config.format.grouping = enabled;
+                    }
+                }
+                "hex-grouping" => {
+                    if let Some(hex_grouping) = from_name(&HEX_GROUPINGS, value) {
+                        config.format.hex_grouping = hex_grouping;
+                    }
+                }
+                "negatives" => {
+                    if let Some(negatives) = from_name(&NEGATIVES, value) {
+                        config.format.negatives = negatives;
+                    }
+                }
+                "history-limit" => {
+                    if let Ok(limit) = value.parse() {
+                        config.history_limit = limit;
+                    }
+                }
+                "max-length" => {
+                    // A shorter limit would keep results from replacing the expression
+                    if let Ok(length) = value.parse::<usize>() {
+                        config.max_length = length.max(MIN_MAX_LENGTH);
+                    }
+                }
+                "mode" => {
+                    if let Some(mode) = from_name(&MODES, value) {
+                        config.mode = mode;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        config
+    }
+
+    // Save the settings, one per line as "key = value"
+    pub fn save(&self) {
+        let settings = [
+            ("word-size", self.options.word_size.to_string()),
+            (
+                "overflow",
+                to_name(&OVERFLOWS, self.options.overflow).to_string(),
+            ),
+            ("base", to_name(&BASES, self.options.base).to_string()),
+            (
+                "implicit-multiplication",
+                self.options.implicit_multiplication.to_string(),
+            ),
+            ("theme", to_name(&THEMES, self.theme).to_string()),
+            ("digit-grouping", self.format.grouping.to_string()),
+            (
+                "hex-grouping",
+                to_name(&HEX_GROUPINGS, self.format.hex_grouping).to_string(),
+            ),
+            (
+                "negatives",
+                to_name(&NEGATIVES, self.format.negatives).to_string(),
+            ),
+            ("history-limit", self.history_limit.to_string()),
+            ("max-length", self.max_length.to_string()),
+            ("mode", to_name(&MODES, self.mode).to_string()),
+        ];
+
+        save_lines(
+            &config_file(CONFIG_FILE),
+            settings
+                .into_iter()
+                .map(|(key, value)| format!("{key} = {value}")),
+        );
+    }
+}
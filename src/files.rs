@@ -0,0 +1,25 @@
+use gtk::glib;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Get a file the calculator keeps between sessions, in the XDG data directory
+pub fn data_file(name: &str) -> PathBuf {
+    glib::user_data_dir().join("calculator").join(name)
+}
+
+// Get a file the user configures the calculator with, in the XDG config directory
+pub fn config_file(name: &str) -> PathBuf {
+    glib::user_config_dir().join("calculator").join(name)
+}
+
+// Save lines to a file, creating the directory it is in if needed
+pub fn save_lines(path: &Path, lines: impl IntoIterator<Item = String>) {
+    let text: String = lines.into_iter().map(|line| line + "\n").collect();
+
+    // Losing a saved file is not worth interrupting the user for
+    if let Some(dir) = path.parent() {
+        _ = fs::create_dir_all(dir);
+    }
+    _ = fs::write(path, text);
+}
@@ -0,0 +1,86 @@
+use crate::files::{data_file, save_lines};
+
+use std::fs;
+
+// Define how many entries the history keeps unless told otherwise
+pub const DEFAULT_LIMIT: usize = 100;
+
+// Define an entry of the calculation history, an evaluated expression and its result
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub expression: String,
+    pub result: String,
+}
+
+// Define the calculation history, oldest entry first, and how many entries it keeps
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    pub limit: usize,
+}
+
+impl History {
+    // Create an empty history that keeps a number of entries
+    pub fn new(limit: usize) -> History {
+        History {
+            entries: Vec::new(),
+            limit,
+        }
+    }
+
+    // Load the history saved by an earlier session, keeping the newest entries
+    pub fn load(limit: usize) -> History {
+        // A missing or unreadable file just means there is no history yet
+        let entries = fs::read_to_string(data_file("history"))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(expression, result)| HistoryEntry {
+                expression: expression.to_string(),
+                result: result.to_string(),
+            })
+            .collect();
+
+        let mut history = History { entries, limit };
+        history.set_limit(limit);
+        history
+    }
+
+    // Save the history, one entry per line with the expression and result separated by a tab
+    pub fn save(&self) {
+        save_lines(
+            &data_file("history"),
+            self.entries
+                .iter()
+                .map(|entry| format!("{}\t{}", entry.expression, entry.result)),
+        );
+    }
+
+    // Add an entry, dropping the oldest ones beyond the limit
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+        self.set_limit(self.limit);
+    }
+
+    // Change how many entries are kept, dropping the oldest ones beyond it
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.entries.len() > limit {
+            self.entries.drain(..self.entries.len() - limit);
+        }
+    }
+
+    // Find the indices of the entries whose expression or result contains the query
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let query = query.trim().to_lowercase();
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| {
+                entry.expression.to_lowercase().contains(&query)
+                    || entry.result.to_lowercase().contains(&query)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
@@ -0,0 +1,159 @@
+use crate::logic::{format_result, Format, Options};
+use crate::types::Int;
+
+use gtk::glib;
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Define the translation catalogs, in the gettext PO format, (language, catalog)
+// po/calculator.pot lists every string to translate
+const CATALOGS: [(&str, &str); 1] = [("de", include_str!("../po/de.po"))];
+
+// Define how numbers are written in each language, the first is used for languages that
+// aren't listed, (language, digit group separator, minus sign)
+const NUMBER_FORMATS: [(&str, &str, &str); 6] = [
+    ("en", ",", "-"),
+    ("de", ".", "−"),
+    ("es", ".", "−"),
+    ("fr", "\u{202f}", "−"),
+    ("it", ".", "−"),
+    ("sv", "\u{202f}", "−"),
+];
+
+// Define the language the calculator is shown in, its translations and how it writes numbers
+struct Locale {
+    translations: HashMap<String, String>,
+    group_separator: &'static str,
+    minus_sign: &'static str,
+}
+
+// Read the string of a PO line, e.g. "\"Clear \\\"M\\\"\"" is "Clear \"M\""
+fn po_string(text: &str) -> String {
+    let text = text.trim();
+    let text = text.strip_prefix('"').unwrap_or(text);
+    let text = text.strip_suffix('"').unwrap_or(text);
+
+    let mut string = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some(escaped) => string.push(escaped),
+                None => {}
+            }
+        } else {
+            string.push(c);
+        }
+    }
+    string
+}
+
+// Read the translations of a PO catalog, leaving out the untranslated strings and the header
+fn parse_catalog(catalog: &str) -> HashMap<String, String> {
+    let mut translations = HashMap::new();
+    let mut msgid = String::new();
+    let mut msgstr = String::new();
+    // Note which of the two strings a continued line adds to
+    let mut in_msgstr = false;
+
+    let mut add = |msgid: &mut String, msgstr: &mut String| {
+        if !msgid.is_empty() && !msgstr.is_empty() {
+            translations.insert(std::mem::take(msgid), std::mem::take(msgstr));
+        }
+        msgid.clear();
+        msgstr.clear();
+    };
+
+    for line in catalog.lines().map(str::trim) {
+        if let Some(text) = line.strip_prefix("msgid ") {
+            add(&mut msgid, &mut msgstr);
+            msgid = po_string(text);
+            in_msgstr = false;
+        } else if let Some(text) = line.strip_prefix("msgstr ") {
+            msgstr = po_string(text);
+            in_msgstr = true;
+        } else if line.starts_with('"') {
+            if in_msgstr {
+                msgstr.push_str(&po_string(line));
+            } else {
+                msgid.push_str(&po_string(line));
+            }
+        }
+    }
+    add(&mut msgid, &mut msgstr);
+
+    translations
+}
+
+// Find the language of the user, the first of their languages the calculator has a catalog or a
+// way of writing numbers for, e.g. "de" for "de_DE.UTF-8"
+fn locale() -> &'static Locale {
+    static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+    LOCALE.get_or_init(|| {
+        let languages: Vec<String> = glib::language_names()
+            .iter()
+            .map(|name| {
+                name.split(['_', '.', '@'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+
+        let translations = languages
+            .iter()
+            .find_map(|language| CATALOGS.iter().find(|(name, _)| name == language))
+            .map(|(_, catalog)| parse_catalog(catalog))
+            .unwrap_or_default();
+        let (_, group_separator, minus_sign) = languages
+            .iter()
+            .find_map(|language| NUMBER_FORMATS.iter().find(|(name, _, _)| name == language))
+            .unwrap_or(&NUMBER_FORMATS[0]);
+
+        Locale {
+            translations,
+            group_separator,
+            minus_sign,
+        }
+    })
+}
+
+// Translate a string into the language of the user, or keep it if it has no translation
+pub fn gettext(msgid: &str) -> String {
+    locale()
+        .translations
+        .get(msgid)
+        .cloned()
+        .unwrap_or_else(|| msgid.to_string())
+}
+
+// Translate a string with placeholders, then fill them in, e.g. "Clear {name}"
+pub fn gettext_with(msgid: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(gettext(msgid), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+// Get the separator of digit groups in the language of the user
+pub fn group_separator() -> &'static str {
+    locale().group_separator
+}
+
+// Write a number with the minus sign of the language of the user, e.g. "-1234" is "−1234"
+// in German, which the tokenizer reads back
+pub fn localize_number(number: &str) -> String {
+    match number.strip_prefix('-') {
+        Some(unsigned) => format!("{}{unsigned}", locale().minus_sign),
+        None => number.to_string(),
+    }
+}
+
+// Write a result in a format the way the language of the user does, with its digit group
+// separator and minus sign, e.g. -1234 is "−1.234" in German with the digits grouped
+pub fn localize_result(value: Int, options: Options, format: Format) -> String {
+    localize_number(&format_result(value, options, format, group_separator()))
+}
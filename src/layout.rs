@@ -0,0 +1,294 @@
+use crate::config::{self, Mode, MODES};
+use crate::files::config_file;
+use crate::i18n::{gettext, gettext_with};
+use crate::logic::tokenize;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+// Define the built-in layouts of the modes, (mode, layout)
+const LAYOUTS: [(Mode, &str); 3] = [
+    (Mode::Basic, include_str!("layouts/basic.layout")),
+    (Mode::Scientific, include_str!("layouts/scientific.layout")),
+    (Mode::Programmer, include_str!("layouts/programmer.layout")),
+];
+
+// Define the actions a key can run, "insert" inserts the token given after it
+pub const ACTIONS: [&str; 9] = [
+    "insert",
+    "clear",
+    "clear-entry",
+    "backspace",
+    "evaluate",
+    "memory-clear",
+    "memory-recall",
+    "memory-add",
+    "memory-subtract",
+];
+
+// Define how many rows and columns a layout can have, which is far more than fit on a screen
+const MAX_GRID_SIZE: i32 = 32;
+
+// Define a key of a layout, where it is in the grid, how many columns and rows it spans
+// and what it does
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    pub label: String,
+    pub row: i32,
+    pub column: i32,
+    pub width: i32,
+    pub height: i32,
+    // One of ACTIONS, and the token to insert for "insert"
+    pub action: String,
+    pub token: Option<String>,
+    pub tooltip: Option<String>,
+    pub class: Option<String>,
+}
+
+// Get the file the user can replace the layout of a mode with, in the XDG config directory
+pub fn path(mode: Mode) -> PathBuf {
+    config_file("layouts").join(format!("{}.layout", config::to_name(&MODES, mode)))
+}
+
+// Read a position, "row column" with an optional "width height" after it
+fn parse_position(field: &str) -> Result<(i32, i32, i32, i32), String> {
+    let numbers: Vec<i32> = field
+        .split_whitespace()
+        .map(|number| number.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            gettext_with(
+                "\"{field}\" is not a position of whole numbers",
+                &[("field", field)],
+            )
+        })?;
+
+    match numbers[..] {
+        [row, column] => Ok((row, column, 1, 1)),
+        [_, _, width, height] if width < 1 || height < 1 => Err(gettext_with(
+            "\"{field}\" spans less than one column or row",
+            &[("field", field)],
+        )),
+        [row, column, width, height] => Ok((row, column, width, height)),
+        _ => Err(gettext_with(
+            "\"{field}\" is not \"row column\" or \"row column width height\"",
+            &[("field", field)],
+        )),
+    }
+    .and_then(|(row, column, width, height)| {
+        // A key has to end inside the grid, which also keeps the ends from overflowing
+        let ends_inside = |start: i32, span: i32| {
+            start >= 0
+                && start
+                    .checked_add(span)
+                    .is_some_and(|end| end <= MAX_GRID_SIZE)
+        };
+        if ends_inside(row, height) && ends_inside(column, width) {
+            Ok((row, column, width, height))
+        } else {
+            Err(gettext_with(
+                "\"{field}\" is outside the grid of {size} rows and columns",
+                &[("field", field), ("size", &MAX_GRID_SIZE.to_string())],
+            ))
+        }
+    })
+}
+
+// Check if a token is a digit of another base or a base prefix, which can only be read as
+// part of a number
+pub fn is_number_part(token: &str) -> bool {
+    token.chars().all(|c| c.is_ascii_hexdigit())
+        || token.len() == 2 && token.starts_with('0') && "xob".contains(&token[1..])
+}
+
+// Read an action, one of ACTIONS and the token to insert after "insert"
+fn parse_action(field: &str) -> Result<(String, Option<String>), String> {
+    let (action, token) = match field.split_once(char::is_whitespace) {
+        Some((action, token)) => (action, Some(token.trim().to_string())),
+        None => (field, None),
+    };
+
+    if !ACTIONS.contains(&action) {
+        return Err(gettext_with(
+            "\"{action}\" is not an action, it can be one of {actions}",
+            &[("action", action), ("actions", &ACTIONS.join(", "))],
+        ));
+    }
+    match (action, token) {
+        ("insert", None) => Err(gettext(
+            "\"insert\" needs a token to insert, e.g. \"insert 7\"",
+        )),
+        ("insert", Some(token)) if tokenize(&token).is_err() && !is_number_part(&token) => {
+            Err(gettext_with(
+                "\"{token}\" can't be read as part of an expression",
+                &[("token", &token)],
+            ))
+        }
+        ("insert", token) => Ok((action.to_string(), token)),
+        (_, Some(_)) => Err(gettext_with(
+            "\"{action}\" doesn't take a token",
+            &[("action", action)],
+        )),
+        (_, None) => Ok((action.to_string(), None)),
+    }
+}
+
+// Read a line of a layout, "label, position, action" with an optional class and tooltip after it
+fn parse_key(line: &str) -> Result<Key, String> {
+    let fields: Vec<&str> = line.splitn(5, ',').map(str::trim).collect();
+    if fields.len() < 3 {
+        return Err(gettext("expected \"label, position, action\""));
+    }
+    if fields[0].is_empty() {
+        return Err(gettext("a key needs a label"));
+    }
+
+    let (row, column, width, height) = parse_position(fields[1])?;
+    let (action, token) = parse_action(fields[2])?;
+    let optional = |index: usize| {
+        fields
+            .get(index)
+            .filter(|field| !field.is_empty())
+            .map(|field| field.to_string())
+    };
+
+    Ok(Key {
+        label: fields[0].to_string(),
+        row,
+        column,
+        width,
+        height,
+        action,
+        token,
+        class: optional(3),
+        tooltip: optional(4),
+    })
+}
+
+// Read a layout, one key per line, skipping empty lines and comments starting with "#",
+// or return why it can't be read
+pub fn parse(text: &str) -> Result<Vec<Key>, String> {
+    let mut keys: Vec<Key> = Vec::new();
+    // Note which key takes each cell, so that keys can't be laid over each other
+    let mut cells: HashMap<(i32, i32), String> = HashMap::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line_number = (index + 1).to_string();
+        let key = parse_key(line).map_err(|message| {
+            gettext_with(
+                "line {line}: {message}",
+                &[("line", &line_number), ("message", &message)],
+            )
+        })?;
+        for row in key.row..key.row + key.height {
+            for column in key.column..key.column + key.width {
+                if let Some(label) = cells.insert((row, column), key.label.clone()) {
+                    return Err(gettext_with(
+                        "line {line}: \"{key}\" overlaps \"{label}\" at row {row}, column {column}",
+                        &[
+                            ("line", &line_number),
+                            ("key", &key.label),
+                            ("label", &label),
+                            ("row", &row.to_string()),
+                            ("column", &column.to_string()),
+                        ],
+                    ));
+                }
+            }
+        }
+        keys.push(key);
+    }
+
+    if keys.is_empty() {
+        return Err(gettext("there are no keys"));
+    }
+    Ok(keys)
+}
+
+// Load the layout of a mode, the user's if there is one and the built-in one otherwise
+// A user layout that can't be read is replaced by the built-in one, along with why
+pub fn load(mode: Mode) -> (Vec<Key>, Option<String>) {
+    let built_in = LAYOUTS
+        .iter()
+        .find(|(layout_mode, _)| *layout_mode == mode)
+        .map(|(_, layout)| parse(layout).expect("Could not read a built-in layout."))
+        .unwrap_or_default();
+
+    let path = path(mode);
+    let Ok(text) = fs::read_to_string(&path) else {
+        return (built_in, None);
+    };
+
+    match parse(&text) {
+        Ok(keys) => (keys, None),
+        Err(message) => (built_in, Some(format!("{}: {message}", path.display()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_layouts_can_be_read() {
+        for (mode, layout) in LAYOUTS {
+            assert!(parse(layout).is_ok(), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn keys_are_read_with_their_optional_fields() {
+        let keys =
+            parse("# comment\n\nc₀, 7 0 2 1, insert 299792458, constant, Speed of light, in m/s")
+                .unwrap();
+        assert_eq!(
+            keys,
+            [Key {
+                label: "c₀".to_string(),
+                row: 7,
+                column: 0,
+                width: 2,
+                height: 1,
+                action: "insert".to_string(),
+                token: Some("299792458".to_string()),
+                class: Some("constant".to_string()),
+                tooltip: Some("Speed of light, in m/s".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn errors_name_their_line() {
+        let error = parse("7, 0 0, insert 7\n8, 0 0, insert 8").unwrap_err();
+        assert!(error.starts_with("line 2:"), "{error}");
+        assert!(parse("7, 0 0, press 7").unwrap_err().starts_with("line 1:"));
+        assert!(parse("7, 0 0, clear 7").is_err());
+        assert!(parse("7, 0 0, insert").is_err());
+        assert!(parse("7, 0 0 0 1, insert 7").is_err());
+        assert!(parse("# only a comment").is_err());
+    }
+
+    #[test]
+    fn keys_have_to_end_inside_the_grid() {
+        assert!(parse("7, 31 31, insert 7").is_ok());
+        assert!(parse("7, 32 0, insert 7").is_err());
+        assert!(parse("7, -1 0, insert 7").is_err());
+        // A huge span would overflow the end of the key, and take forever to lay out
+        let error = parse("7, 0 0 2147483647 2147483647, insert 7").unwrap_err();
+        assert!(error.starts_with("line 1:"), "{error}");
+        assert!(parse("7, 1 1 2147483647 1, insert 7").is_err());
+    }
+
+    #[test]
+    fn number_parts_can_be_inserted() {
+        assert!(parse("F, 0 0, insert f").is_ok());
+        assert!(parse("0x, 0 0, insert 0x").is_ok());
+        assert!(parse("?, 0 0, insert @").is_err());
+    }
+}
@@ -1,8 +1,9 @@
+use super::options::Overflow;
 use crate::types::*;
 
 // Define constants for square root function
 const D_INIT: Int = 1 << (Int::BITS - 2);
-const SEARCH_ITER: u8 = ((Int::BITS + 1) / 2) as u8;
+const SEARCH_ITER: u8 = Int::BITS.div_ceil(2) as u8;
 
 // Calculate the absolute value of a signed integer
 fn abs(n: Int) -> Int {
@@ -14,7 +15,7 @@ fn abs(n: Int) -> Int {
 }
 
 // Calculate the two's complement of a signed integer (equivalent to negating its value)
-fn negate(n: Int) -> Int {
+pub fn negate(n: Int) -> Int {
     let inverted_bits = !n;
     add(inverted_bits, 1)
 }
@@ -217,6 +218,69 @@ pub fn divide(dividend: Int, divisor: Int) -> Int {
     }
 }
 
+// Calculate the remainder of the division of two signed integers, which has the sign of the dividend
+pub fn remainder(dividend: Int, divisor: Int) -> Int {
+    // Check for special cases where the divisor is 0, 1, or -1
+    if divisor == 0 {
+        // If divisor is 0, nothing can be divided out, so return dividend unchanged
+        return dividend;
+    } else if divisor == 1 || divisor == -1 {
+        // If divisor is 1 or -1, the division is always exact, so return 0
+        return 0;
+    }
+
+    // Subtract the whole multiples of divisor from dividend, what is left is the remainder
+    subtract(dividend, multiply(divide(dividend, divisor), divisor))
+}
+
+// Calculate the quotient of two signed integers, rounded half away from zero
+pub fn divide_rounded(dividend: Int, divisor: Int) -> Int {
+    // Calculate the truncated quotient and the remainder it leaves
+    let quotient = divide(dividend, divisor);
+    let rest = abs(remainder(dividend, divisor));
+
+    // Check if the remainder is at least half of the divisor
+    if divisor != 0 && rest >= subtract(abs(divisor), rest) {
+        // If it is, round the quotient away from zero
+        if (dividend < 0) != (divisor < 0) {
+            subtract(quotient, 1)
+        } else {
+            add(quotient, 1)
+        }
+    } else {
+        // Otherwise, the truncated quotient is already the nearest
+        quotient
+    }
+}
+
+// Calculate the given percentage of a signed integer, rounded half away from zero
+pub fn percent_of(base: Int, percent: Int) -> Int {
+    // Split base into whole hundreds and the rest, so that multiplying by percent
+    // only overflows if the result does
+    let hundreds = divide(base, 100);
+    let rest = remainder(base, 100);
+
+    // Every whole hundred contributes percent exactly, only the rest has to be rounded
+    add(
+        multiply(hundreds, percent),
+        divide_rounded(multiply(rest, percent), 100),
+    )
+}
+
+// Calculate the whole that a signed integer is the given percentage of, rounded half away from zero
+pub fn percent_base(part: Int, percent: Int) -> Int {
+    // Split part into whole multiples of percent and the rest, so that multiplying by 100
+    // only overflows if the result does
+    let multiples = divide(part, percent);
+    let rest = remainder(part, percent);
+
+    // Every whole multiple contributes 100 exactly, only the rest has to be rounded
+    add(
+        multiply(multiples, 100),
+        divide_rounded(multiply(rest, 100), percent),
+    )
+}
+
 // Calculate the integer square root of a signed integer
 pub fn square_root(b: Int) -> Int {
     // Check for special cases where the input is 0, 1, or -1
@@ -258,8 +322,9 @@ pub fn square_root(b: Int) -> Int {
     root
 }
 
-// Calculate base raised to the power of exponent
-pub fn power(mut base: Int, mut exponent: Int) -> Int {
+// Calculate base raised to the power of exponent within a word, fitting every product into it
+// so that the result is exact when it wraps around and can't overflow the Int type
+pub fn power(mut base: Int, mut exponent: Int, word_size: u32, overflow: Overflow) -> Int {
     // Check for special cases where base is 0 or exponent is 0 or 1
     if base == 0 {
         // If base is 0, return 0
@@ -279,19 +344,96 @@ pub fn power(mut base: Int, mut exponent: Int) -> Int {
         // Check if the least significant bit of exponent is set
         if (exponent & 1) == 1 {
             // If it is, multiply result by base
-            result = multiply(result, base);
-
-            // Check if the result has reached the maximum or minimum representable value
-            if result == Int::MAX || result == Int::MIN {
-                // If so, return it immediately without performing any further calculations
-                return result;
-            }
+            result = fit(multiply(result, base), word_size, overflow);
         }
         // Square base by multiplying it by itself
-        base = multiply(base, base);
+        base = fit(multiply(base, base), word_size, overflow);
         // Right-shift exponent by 1 bit
         exponent >>= 1;
     }
     // Return the final value of result as the result
     result
 }
+
+// Fit a number into a word of word_size bits, either by saturating it or by wrapping it around
+pub fn fit(value: Int, word_size: u32, overflow: Overflow) -> Int {
+    // Shift the bits above the word out and back in as copies of the word's sign bit
+    let shift = Int::BITS - word_size;
+    let wrapped = (value << shift) >> shift;
+
+    match overflow {
+        Overflow::Wrap => wrapped,
+        // If wrapping changed the number, it doesn't fit, so return the bound on its side
+        Overflow::Saturate if wrapped != value => {
+            let max = Int::MAX >> shift;
+            if value < 0 {
+                !max
+            } else {
+                max
+            }
+        }
+        Overflow::Saturate => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saturate_stops_at_the_bounds_of_the_word() {
+        assert_eq!(fit(0xffff, 16, Overflow::Saturate), 0x7fff);
+        assert_eq!(fit(-0x8001, 16, Overflow::Saturate), -0x8000);
+        assert_eq!(fit(128, 8, Overflow::Saturate), 127);
+        assert_eq!(fit(-128, 8, Overflow::Saturate), -128);
+        assert_eq!(fit(100, 8, Overflow::Saturate), 100);
+    }
+
+    #[test]
+    fn wrap_keeps_the_low_bits() {
+        assert_eq!(fit(0xffff, 16, Overflow::Wrap), -1);
+        assert_eq!(fit(0x8000, 16, Overflow::Wrap), -0x8000);
+        assert_eq!(fit(128, 8, Overflow::Wrap), -128);
+        assert_eq!(fit(0x1_0000_0005, 32, Overflow::Wrap), 5);
+    }
+
+    #[test]
+    fn powers_fit_into_the_word() {
+        assert_eq!(power(2, 15, 16, Overflow::Saturate), 0x7fff);
+        assert_eq!(power(2, 15, 16, Overflow::Wrap), -0x8000);
+        assert_eq!(power(-2, 3, 8, Overflow::Saturate), -8);
+    }
+
+    #[test]
+    fn rounding_is_half_away_from_zero() {
+        assert_eq!(divide_rounded(7, 2), 4);
+        assert_eq!(divide_rounded(-7, 2), -4);
+        assert_eq!(divide_rounded(7, -2), -4);
+        assert_eq!(divide_rounded(-7, -2), 4);
+        assert_eq!(divide_rounded(-4, 3), -1);
+        assert_eq!(divide_rounded(-5, 3), -2);
+        assert_eq!(percent_of(-15, 10), -2);
+        assert_eq!(percent_of(-14, 10), -1);
+        assert_eq!(percent_base(-1, 2), -50);
+        assert_eq!(percent_base(-1, 3), -33);
+    }
+
+    #[test]
+    fn percentages_split_off_whole_hundreds() {
+        assert_eq!(percent_of(200, 10), 20);
+        assert_eq!(percent_of(250, 10), 25);
+        assert_eq!(percent_of(0x7fff_ffff, 100), 0x7fff_ffff);
+        assert_eq!(percent_base(50, 200), 25);
+        assert_eq!(percent_base(20, 10), 200);
+    }
+
+    #[test]
+    fn remainders_take_the_sign_of_the_dividend() {
+        assert_eq!(remainder(7, 3), 1);
+        assert_eq!(remainder(-7, 3), -1);
+        assert_eq!(remainder(7, -3), 1);
+        assert_eq!(remainder(-7, -3), -1);
+        assert_eq!(remainder(7, 0), 7);
+        assert_eq!(divide(-7, 2), -3);
+    }
+}
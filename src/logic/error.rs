@@ -0,0 +1,73 @@
+// Define the ways an expression can fail to be calculated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    // A character that is not part of any number, operator or parenthesis
+    UnknownCharacter,
+    // An operator or closing parenthesis where a number is expected
+    ExpectedOperand,
+    // A number or opening parenthesis where an operator is expected
+    ExpectedOperator,
+    // A closing parenthesis without an opening one
+    UnmatchedParenthesis,
+    // An opening parenthesis without a closing one
+    UnclosedParenthesis,
+    // A ":" without the "?" it belongs to
+    UnmatchedColon,
+    // A "?" without its ":"
+    UnclosedConditional,
+    // An expression that ends where a number is expected
+    Incomplete,
+    // Anything else that can't be made sense of
+    Malformed,
+    // A name that is not a variable, or not one that can be set
+    UnknownName,
+    // A division or remainder by zero, which is written correctly but has no result
+    DivisionByZero,
+    // A square root of a negative number, which has no result either
+    NegativeSquareRoot,
+}
+
+// Define an error in an expression and the index of the token it was found at, if any
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalcError {
+    pub kind: ErrorKind,
+    pub index: Option<usize>,
+}
+
+impl ErrorKind {
+    // Describe the error to the user, in English, the catalogs of the interface translate it
+    pub fn message(self) -> &'static str {
+        match self {
+            ErrorKind::UnknownCharacter => "Unknown character",
+            ErrorKind::ExpectedOperand => "Expected a number",
+            ErrorKind::ExpectedOperator => "Expected an operator",
+            ErrorKind::UnmatchedParenthesis => "Closing parenthesis without an opening one",
+            ErrorKind::UnclosedParenthesis => "Opening parenthesis without a closing one",
+            ErrorKind::UnmatchedColon => "\":\" without a \"?\"",
+            ErrorKind::UnclosedConditional => "\"?\" without a \":\"",
+            ErrorKind::Incomplete => "Incomplete expression",
+            ErrorKind::Malformed => "Malformed input",
+            ErrorKind::UnknownName => "Unknown name",
+            ErrorKind::DivisionByZero => "Division by zero",
+            ErrorKind::NegativeSquareRoot => "Square root of a negative number",
+        }
+    }
+
+    // Check if the error is in what the expression means rather than how it is written
+    pub fn is_domain_error(self) -> bool {
+        matches!(
+            self,
+            ErrorKind::DivisionByZero | ErrorKind::NegativeSquareRoot
+        )
+    }
+}
+
+impl CalcError {
+    // Create an error found at the token with the given index
+    pub fn at(kind: ErrorKind, index: usize) -> CalcError {
+        CalcError {
+            kind,
+            index: Some(index),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::options::Options;
+use crate::types::*;
+
+// Define the size of digit groups in decimal, whose separator depends on the language,
+// e.g. "1,234,567"
+const GROUP_SIZE: usize = 3;
+
+// Define the separator of digit groups in other bases, and the size of the groups in octal
+// and binary, e.g. "0o7 777" and "0b1111 1111"
+const PREFIXED_GROUP_SEPARATOR: &str = " ";
+const OCTAL_GROUP_SIZE: usize = 3;
+const BINARY_GROUP_SIZE: usize = 4;
+
+// Define the bases a number can be written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base {
+    Decimal,
+    Hexadecimal,
+    Octal,
+    Binary,
+}
+
+// Define how many hexadecimal digits make a group
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexGrouping {
+    // One digit, e.g. "0xf f f f f f"
+    Nibbles,
+    // Two digits, e.g. "0xff ff ff"
+    Bytes,
+    // Four digits, e.g. "0xff ffff"
+    Words,
+}
+
+impl HexGrouping {
+    fn size(self) -> usize {
+        match self {
+            HexGrouping::Nibbles => 1,
+            HexGrouping::Bytes => 2,
+            HexGrouping::Words => 4,
+        }
+    }
+}
+
+// Define how negative numbers are written in bases other than decimal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Negatives {
+    // A minus sign in front of the magnitude, e.g. -1 is "-0x1"
+    SignMagnitude,
+    // The bits of the word, e.g. -1 is "0xff" in 8 bits
+    TwosComplement,
+}
+
+// Define how results are written for the user to read, on top of the base of the options
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Format {
+    // Split the digits into groups, e.g. "1,234,567" or "0b1111 1111"
+    pub grouping: bool,
+    pub hex_grouping: HexGrouping,
+    pub negatives: Negatives,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            grouping: false,
+            hex_grouping: HexGrouping::Words,
+            negatives: Negatives::SignMagnitude,
+        }
+    }
+}
+
+// Write a number in a base, other bases than decimal get a prefix and a sign, e.g. -255 is
+// "-0xff", so that the tokenizer reads them back as the same number whatever the word size
+pub fn format_number(value: Int, base: Base) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+
+    match base {
+        Base::Decimal => value.to_string(),
+        Base::Hexadecimal => format!("{sign}{magnitude:#x}"),
+        Base::Octal => format!("{sign}{magnitude:#o}"),
+        Base::Binary => format!("{sign}{magnitude:#b}"),
+    }
+}
+
+// Write a number for the user to read in a format, e.g. -1 in 8 bits is "0xff" in hexadecimal
+// with negative numbers in two's complement, and -1234567 is "-1,234,567" in decimal with the
+// digits grouped by ","
+// Decimal digits are grouped by the separator given, the digits of other bases by spaces
+pub fn format_result(value: Int, options: Options, format: Format, separator: &str) -> String {
+    // In two's complement a negative number is written as the bits of its word, decimal numbers
+    // always have a sign
+    let twos_complement =
+        value < 0 && options.base != Base::Decimal && format.negatives == Negatives::TwosComplement;
+    let (sign, magnitude) = if twos_complement {
+        (
+            "",
+            (value as Uint) & (Uint::MAX >> (Uint::BITS - options.word_size)),
+        )
+    } else if value < 0 {
+        ("-", value.unsigned_abs())
+    } else {
+        ("", value as Uint)
+    };
+
+    let (prefix, digits, size, separator) = match options.base {
+        Base::Decimal => ("", format!("{magnitude}"), GROUP_SIZE, separator),
+        Base::Hexadecimal => (
+            "0x",
+            format!("{magnitude:x}"),
+            format.hex_grouping.size(),
+            PREFIXED_GROUP_SEPARATOR,
+        ),
+        Base::Octal => (
+            "0o",
+            format!("{magnitude:o}"),
+            OCTAL_GROUP_SIZE,
+            PREFIXED_GROUP_SEPARATOR,
+        ),
+        Base::Binary => (
+            "0b",
+            format!("{magnitude:b}"),
+            BINARY_GROUP_SIZE,
+            PREFIXED_GROUP_SEPARATOR,
+        ),
+    };
+
+    if !format.grouping {
+        return format!("{sign}{prefix}{digits}");
+    }
+
+    // Split the digits into groups counted from the last digit
+    let digits: Vec<char> = digits.chars().collect();
+    let groups: Vec<String> = digits
+        .rchunks(size)
+        .rev()
+        .map(|group| group.iter().collect())
+        .collect();
+
+    format!("{sign}{prefix}{}", groups.join(separator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Get options that write results in a base, in a word of 16 bits
+    fn in_base(base: Base) -> Options {
+        Options {
+            word_size: 16,
+            base,
+            ..Options::default()
+        }
+    }
+
+    // Get a format that groups digits, with hexadecimal digits grouped in a way
+    fn grouped(hex_grouping: HexGrouping) -> Format {
+        Format {
+            grouping: true,
+            hex_grouping,
+            ..Format::default()
+        }
+    }
+
+    #[test]
+    fn digits_are_grouped_by_base() {
+        let decimal = in_base(Base::Decimal);
+        let hexadecimal = in_base(Base::Hexadecimal);
+        let format = grouped(HexGrouping::Bytes);
+        assert_eq!(format_result(-1234567, decimal, format, ","), "-1,234,567");
+        assert_eq!(format_result(123, decimal, format, ","), "123");
+        assert_eq!(
+            format_result(0xabcde, hexadecimal, format, ","),
+            "0xa bc de"
+        );
+        assert_eq!(
+            format_result(0xabcde, hexadecimal, grouped(HexGrouping::Words), ","),
+            "0xa bcde"
+        );
+        assert_eq!(
+            format_result(0xabc, hexadecimal, grouped(HexGrouping::Nibbles), ","),
+            "0xa b c"
+        );
+        assert_eq!(
+            format_result(0o7777, in_base(Base::Octal), format, ","),
+            "0o7 777"
+        );
+        assert_eq!(
+            format_result(0xff, in_base(Base::Binary), format, ","),
+            "0b1111 1111"
+        );
+    }
+
+    #[test]
+    fn negatives_are_written_with_a_sign_or_the_bits_of_the_word() {
+        let hexadecimal = in_base(Base::Hexadecimal);
+        let twos_complement = Format {
+            negatives: Negatives::TwosComplement,
+            ..Format::default()
+        };
+        assert_eq!(
+            format_result(-1, hexadecimal, Format::default(), ","),
+            "-0x1"
+        );
+        assert_eq!(
+            format_result(-1, hexadecimal, twos_complement, ","),
+            "0xffff"
+        );
+        assert_eq!(
+            format_result(-0x8000, hexadecimal, twos_complement, ","),
+            "0x8000"
+        );
+        // Decimal results always have a sign
+        assert_eq!(
+            format_result(-1, in_base(Base::Decimal), twos_complement, ","),
+            "-1"
+        );
+    }
+
+    #[test]
+    fn numbers_are_written_to_be_read_back() {
+        assert_eq!(format_number(-255, Base::Hexadecimal), "-0xff");
+        assert_eq!(format_number(5, Base::Binary), "0b101");
+        assert_eq!(format_number(-8, Base::Decimal), "-8");
+    }
+}
@@ -1,4 +1,19 @@
 mod arithmetic;
+mod error;
+mod formatting;
+mod notation;
+mod options;
 mod parsing;
+mod tokenizing;
+mod tree;
 
-pub use self::parsing::calculate;
+pub use self::arithmetic::{add, fit, subtract};
+pub use self::error::ErrorKind;
+pub use self::formatting::{format_number, format_result, Base, Format, HexGrouping, Negatives};
+pub use self::notation::{convert, Notation};
+pub use self::options::{Options, Overflow, WORD_SIZES};
+pub use self::parsing::{
+    calculate_value, explain_percentages, is_single_number, preview, preview_value,
+    single_number_value, trace,
+};
+pub use self::tokenizing::{is_variable_name, scan, scan_with, tokenize};
@@ -0,0 +1,205 @@
+use super::options::Options;
+use super::parsing::*;
+use super::tree::*;
+
+// Define the notations an expression can be written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notation {
+    // Operators are written between their operands, e.g. "1 + 2"
+    Infix,
+    // Operators are written after their operands (Reverse Polish Notation), e.g. "1 2 +"
+    Postfix,
+    // Operators are written before their operands (Polish Notation), e.g. "+ 1 2"
+    Prefix,
+}
+
+// Define the symbols of the operators that are never typed as they are named, which postfix and
+// prefix notation write as a single token with a fixed arity, (operator, symbol)
+// The unary minus is "~", so that it can't be taken for the binary one, e.g. "5 ~" is "-5",
+// and the conditional is "?" with its three operands, e.g. "? a b c" is "a ? b : c"
+const SYMBOLS: [(&str, &str); 2] = [("neg", "~"), ("?:", "?")];
+
+// Get the symbol an operator is written as in postfix and prefix notation
+fn to_symbol(op: String) -> String {
+    SYMBOLS
+        .iter()
+        .find(|(operator, _)| *operator == op)
+        .map_or(op, |(_, symbol)| symbol.to_string())
+}
+
+// Get the operator a token of a postfix or prefix expression stands for
+fn from_symbol(token: &String) -> String {
+    SYMBOLS
+        .iter()
+        .find(|(_, symbol)| symbol == token)
+        .map_or_else(|| token.clone(), |(operator, _)| operator.to_string())
+}
+
+// Build an expression tree from an infix expression
+fn tree_from_infix(infix: Vec<String>, options: Options) -> Result<Node, bool> {
+    // Resolve negations and implied multiplications
+    let infix = normalize(infix, options);
+    // Make sure the expression is well formed before reordering it
+    check_infix(&infix).map_err(|_| false)?;
+    // Reorder the expression to postfix and build the tree from that
+    tree_from_postfix(&shunting_yard(infix))
+}
+
+// Write an expression tree as a postfix expression
+fn tree_to_postfix(node: Node, output: &mut Vec<String>) {
+    match node {
+        Node::Operand(value) => output.push(value),
+        Node::Operator(op, operands) => {
+            // Operands first, then the operator
+            for operand in operands {
+                tree_to_postfix(operand, output);
+            }
+            output.push(to_symbol(op));
+        }
+    }
+}
+
+// Write an expression tree as a prefix expression
+fn tree_to_prefix(node: Node, output: &mut Vec<String>) {
+    match node {
+        Node::Operand(value) => output.push(value),
+        Node::Operator(op, operands) => {
+            // The operator first, then the operands
+            output.push(to_symbol(op));
+            for operand in operands {
+                tree_to_prefix(operand, output);
+            }
+        }
+    }
+}
+
+// Check if an operand of the given operator has to be wrapped in parentheses in infix notation
+fn needs_parentheses(op: &str, operand: &Node, is_right: bool) -> bool {
+    if let Node::Operator(operand_op, operands) = operand {
+        let precedence = get_op_precedence(op);
+        let operand_precedence = get_op_precedence(operand_op);
+
+        // A postfix operand is complete as it is, so it never needs parentheses
+        if is_postfix_operator(operand_op) {
+            return false;
+        }
+
+        // A prefix operand takes everything that binds tighter than itself along with it,
+        // so it only needs parentheses on the left of an operator that binds tighter
+        if operands.len() == 1 {
+            return !is_right
+                && (operand_precedence < precedence
+                    || operand_precedence == precedence && get_op_associativity(op) == 1);
+        }
+
+        // A lower precedence operand always needs parentheses, an equal precedence one only
+        // when it sits on the side the operator does not associate towards
+        operand_precedence < precedence
+            || operand_precedence == precedence && (is_right == (get_op_associativity(op) == 0))
+    } else {
+        false
+    }
+}
+
+// Write an expression tree as an infix expression with as few parentheses as possible
+fn tree_to_infix(node: Node, output: &mut Vec<String>) {
+    match node {
+        Node::Operand(value) => output.push(value),
+        Node::Operator(op, operands) => {
+            let arity = operands.len();
+            for (i, operand) in operands.into_iter().enumerate() {
+                // Write the operator, or the part of it, that precedes this operand
+                if arity == 1 && op == "neg" {
+                    // A negation is written as the minus sign it was typed as
+                    output.push("-".to_string());
+                } else if is_prefix_operator(&op) || arity == 2 && i == 1 {
+                    // A prefix operator precedes its operand, a binary one its second operand
+                    output.push(op.clone());
+                } else if arity == 3 && i > 0 {
+                    // A conditional is written as "condition ? then : else"
+                    output.push(if i == 1 { "?" } else { ":" }.to_string());
+                }
+
+                // The middle operand of a conditional is enclosed by "?" and ":" already
+                let is_right = i == arity - 1 && !is_postfix_operator(&op);
+                if !(arity == 3 && i == 1) && needs_parentheses(&op, &operand, is_right) {
+                    output.push("(".to_string());
+                    tree_to_infix(operand, output);
+                    output.push(")".to_string());
+                } else {
+                    tree_to_infix(operand, output);
+                }
+            }
+
+            // A postfix operator follows its operand
+            if is_postfix_operator(&op) {
+                output.push(op);
+            }
+        }
+    }
+}
+
+// Convert an expression from one notation to another
+pub fn convert(
+    input: Vec<String>,
+    from: Notation,
+    to: Notation,
+    options: Options,
+) -> Result<Vec<String>, bool> {
+    // Build an expression tree from the input, reading the symbols postfix and prefix notation
+    // write some operators as
+    let tree = match from {
+        Notation::Infix => tree_from_infix(input, options)?,
+        Notation::Postfix => tree_from_postfix(&input.iter().map(from_symbol).collect::<Vec<_>>())?,
+        Notation::Prefix => tree_from_prefix(&input.iter().map(from_symbol).collect::<Vec<_>>())?,
+    };
+
+    // Write the expression tree in the requested notation
+    let mut output: Vec<String> = Vec::new();
+    match to {
+        Notation::Infix => tree_to_infix(tree, &mut output),
+        Notation::Postfix => tree_to_postfix(tree, &mut output),
+        Notation::Prefix => tree_to_prefix(tree, &mut output),
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::tokenize;
+
+    // Convert an infix expression to another notation, written with spaces between tokens
+    fn from_infix(text: &str, to: Notation) -> String {
+        let input = tokenize(text).expect("Could not read the expression.");
+        convert(input, Notation::Infix, to, Options::default())
+            .expect("Could not convert the expression.")
+            .join(" ")
+    }
+
+    #[test]
+    fn negations_and_conditionals_are_written_as_symbols() {
+        assert_eq!(from_infix("-2^2", Notation::Postfix), "2 2 ^ ~");
+        assert_eq!(from_infix("-2^2", Notation::Prefix), "~ ^ 2 2");
+        assert_eq!(
+            from_infix("1 < 2 ? 3 : 4", Notation::Postfix),
+            "1 2 < 3 4 ?"
+        );
+        assert_eq!(from_infix("1 < 2 ? 3 : 4", Notation::Prefix), "? < 1 2 3 4");
+    }
+
+    #[test]
+    fn symbols_are_read_back() {
+        for text in ["- 2 ^ 2", "1 - - 3", "1 < 2 ? - 3 : 4 x 5"] {
+            for notation in [Notation::Postfix, Notation::Prefix] {
+                let written: Vec<String> = from_infix(text, notation)
+                    .split(' ')
+                    .map(String::from)
+                    .collect();
+                let infix = convert(written, notation, Notation::Infix, Options::default());
+                assert_eq!(infix.map(|tokens| tokens.join(" ")), Ok(text.to_string()));
+            }
+        }
+    }
+}
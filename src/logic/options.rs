@@ -0,0 +1,38 @@
+use super::formatting::Base;
+
+// Define the word sizes in bits that numbers can be limited to
+pub const WORD_SIZES: [u32; 3] = [8, 16, 32];
+
+// Define what happens to a result that doesn't fit into the word size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    // Stop at the largest or smallest number, e.g. 127 + 1 is 127 in 8 bits
+    Saturate,
+    // Keep the low bits in two's complement, e.g. 127 + 1 is -128 in 8 bits
+    Wrap,
+}
+
+// Define the options that change how expressions are parsed and evaluated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    // Multiply adjacent operands that aren't both numbers, e.g. "2 ( 3 )" or "3 √ 16", instead of
+    // rejecting them
+    pub implicit_multiplication: bool,
+    // Limit every number to a word of this many bits, one of WORD_SIZES
+    pub word_size: u32,
+    // Fit results that don't fit into the word size this way
+    pub overflow: Overflow,
+    // Write results in this base
+    pub base: Base,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            implicit_multiplication: true,
+            word_size: 32,
+            overflow: Overflow::Saturate,
+            base: Base::Decimal,
+        }
+    }
+}
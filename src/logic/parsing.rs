@@ -1,19 +1,47 @@
 use super::arithmetic::*;
+use super::error::*;
+use super::options::Options;
+use super::tree::*;
 use crate::types::Int;
 
-// Define supported operators and their precedence and associativity
-// (operator, precedence, associativity)
-const OPERATORS: [(&str, u8, u8); 6] = [
-    ("^", 5, 1),
-    ("√", 4, 1),
-    ("x", 3, 0),
-    ("÷", 3, 0),
-    ("+", 2, 0),
-    ("-", 2, 0),
+// Define supported operators and their precedence, associativity and arity
+// (operator, precedence, associativity, arity)
+// A unary operator is written before its operand if it is right associative, after it if left
+// "neg" is the unary minus, it is never typed but stands in for a "-" that precedes an operand
+// "?:" is the conditional "condition ? then : else", it is typed as its two halves "?" and ":"
+const OPERATORS: [(&str, u8, u8, u8); 19] = [
+    ("%", 10, 0, 1),
+    ("^", 9, 1, 2),
+    ("√", 8, 1, 1),
+    ("neg", 8, 1, 1),
+    ("!", 8, 1, 1),
+    ("x", 7, 0, 2),
+    ("÷", 7, 0, 2),
+    ("mod", 7, 0, 2),
+    ("+", 6, 0, 2),
+    ("-", 6, 0, 2),
+    ("<", 5, 0, 2),
+    ("<=", 5, 0, 2),
+    (">", 5, 0, 2),
+    (">=", 5, 0, 2),
+    ("==", 4, 0, 2),
+    ("!=", 4, 0, 2),
+    ("&&", 3, 0, 2),
+    ("||", 2, 0, 2),
+    ("?:", 1, 1, 3),
 ];
 
+// Define a note on how a percentage is applied, a template with "{a}", "{b}" and "{part}" in it
+// and their values, (template, [a, b, part])
+pub type PercentNote = (&'static str, [Int; 3]);
+
+// Check if a string is a supported operator
+pub fn is_operator(op: &str) -> bool {
+    OPERATORS.iter().any(|operator| operator.0 == op)
+}
+
 // Get precedence of a given operator
-fn get_op_precedence(op: &str) -> u8 {
+pub fn get_op_precedence(op: &str) -> u8 {
     for operator in OPERATORS {
         if operator.0 == op {
             return operator.1;
@@ -23,7 +51,7 @@ fn get_op_precedence(op: &str) -> u8 {
 }
 
 // Get associativity of a given operator
-fn get_op_associativity(op: &str) -> u8 {
+pub fn get_op_associativity(op: &str) -> u8 {
     for operator in OPERATORS {
         if operator.0 == op {
             return operator.2;
@@ -32,34 +60,206 @@ fn get_op_associativity(op: &str) -> u8 {
     panic!("Operator not found");
 }
 
-// Get result of a given operator on two numbers
-fn get_op_result(op: &str, a: Int, b: Int) -> Result<Int, bool> {
+// Get arity (number of operands) of a given operator
+pub fn get_op_arity(op: &str) -> u8 {
+    for operator in OPERATORS {
+        if operator.0 == op {
+            return operator.3;
+        }
+    }
+    panic!("Operator not found");
+}
+
+// Check if a string is a prefix operator, i.e. a unary operator written before its operand
+pub fn is_prefix_operator(op: &str) -> bool {
+    is_operator(op) && get_op_arity(op) == 1 && get_op_associativity(op) == 1
+}
+
+// Check if a string is a postfix operator, i.e. a unary operator written after its operand
+pub fn is_postfix_operator(op: &str) -> bool {
+    is_operator(op) && get_op_arity(op) == 1 && get_op_associativity(op) == 0
+}
+
+// Convert a boolean to the number a comparison or logical operator evaluates to
+fn from_bool(value: bool) -> Int {
+    if value {
+        1
+    } else {
+        0
+    }
+}
+
+// Get result of a given operator on two numbers, a unary operator only uses b
+fn get_op_result(op: &str, a: Int, b: Int, options: Options) -> Result<Int, ErrorKind> {
     // Check if the given operator is a supported one
-    if OPERATORS[0].0 == op {
-        return Ok(power(a, b));
-    } else if OPERATORS[1].0 == op {
-        return Ok(square_root(b));
-    } else if OPERATORS[2].0 == op {
-        return Ok(multiply(a, b));
-    } else if OPERATORS[3].0 == op {
-        return Ok(divide(a, b));
-    } else if OPERATORS[4].0 == op {
-        return Ok(add(a, b));
-    } else if OPERATORS[5].0 == op {
-        return Ok(subtract(a, b));
-    }
-    // Otherwise, return a error
-    Err(false)
+    match op {
+        "^" => Ok(power(a, b, options.word_size, options.overflow)),
+        // No whole number squares to a negative one
+        "√" if b < 0 => Err(ErrorKind::NegativeSquareRoot),
+        "√" => Ok(square_root(b)),
+        "neg" => Ok(negate(b)),
+        "!" => Ok(from_bool(b == 0)),
+        "x" => Ok(multiply(a, b)),
+        // Nothing can be divided by zero
+        "÷" | "mod" if b == 0 => Err(ErrorKind::DivisionByZero),
+        "÷" => Ok(divide(a, b)),
+        "mod" => Ok(remainder(a, b)),
+        "+" => Ok(add(a, b)),
+        "-" => Ok(subtract(a, b)),
+        "<" => Ok(from_bool(a < b)),
+        "<=" => Ok(from_bool(a <= b)),
+        ">" => Ok(from_bool(a > b)),
+        ">=" => Ok(from_bool(a >= b)),
+        "==" => Ok(from_bool(a == b)),
+        "!=" => Ok(from_bool(a != b)),
+        // Otherwise, return a error
+        _ => Err(ErrorKind::Malformed),
+    }
 }
 
 // Check if a string is a number
 pub fn string_is_number(s: &str) -> bool {
-    return s.chars().all(char::is_numeric)
-        || s.len() > 1 && s.starts_with('-') && s.chars().skip(1).all(char::is_numeric);
+    s.chars().all(|c| c.is_ascii_digit())
+        || s.len() > 1 && s.starts_with('-') && s.chars().skip(1).all(|c| c.is_ascii_digit())
+}
+
+// Check if a mathematical expression in infix notation is a single number, negative or not
+pub fn is_single_number(input: &[String]) -> bool {
+    let number = input.strip_prefix(&["-".to_string()]).unwrap_or(input);
+    number.len() == 1 && string_is_number(&number[0])
+}
+
+// Read a mathematical expression in infix notation that is a single number as its value, as it
+// is written rather than fitted into a word, e.g. a result saved in the history
+pub fn single_number_value(input: &[String]) -> Option<Int> {
+    if !is_single_number(input) {
+        return None;
+    }
+    let value = parse_number(input.last()?);
+    Some(if input.len() == 2 {
+        negate(value)
+    } else {
+        value
+    })
+}
+
+// Prepare an infix expression for parsing by turning every "-" that precedes an operand
+// into a negation and, if enabled, inserting the multiplication implied by two adjacent
+// operands that aren't both numbers, e.g. "2 ( 3 )" becomes "2 x ( 3 )"
+// An implied multiplication has the same precedence as an explicit one, so "6 ÷ 2 ( 3 )" is 9
+pub fn normalize(infix: Vec<String>, options: Options) -> Vec<String> {
+    normalize_indexed(infix, options).0
+}
+
+// Normalize an infix expression, also noting for each of its tokens the index of the input
+// token it stands for, where an implied multiplication stands for the operand after it
+fn normalize_indexed(infix: Vec<String>, options: Options) -> (Vec<String>, Vec<usize>) {
+    // Initialize output vectors
+    let mut output: Vec<String> = Vec::new();
+    let mut origins: Vec<usize> = Vec::new();
+    // Track whether the previous token ended an operand, and whether it was a number
+    let mut after_operand = false;
+    let mut after_number = false;
+
+    for (index, token) in infix.into_iter().enumerate() {
+        if token == "-" && !after_operand {
+            // A minus sign that does not follow an operand negates what comes after it
+            output.push("neg".to_string());
+            origins.push(index);
+            after_number = false;
+            continue;
+        }
+
+        // Check if token starts an operand, i.e. a number, an opening parenthesis or a prefix operator
+        let starts_operand = string_is_number(&token) || token == "(" || is_prefix_operator(&token);
+        // Two numbers side by side are a missing operator rather than a product, e.g. "2 3"
+        let between_numbers = after_number && string_is_number(&token);
+        if after_operand && starts_operand && !between_numbers && options.implicit_multiplication {
+            // If two operands are adjacent, multiply them
+            output.push("x".to_string());
+            origins.push(index);
+        }
+
+        after_number = string_is_number(&token);
+        after_operand = after_number || token == ")" || is_postfix_operator(&token);
+        output.push(token);
+        origins.push(index);
+    }
+
+    (output, origins)
+}
+
+// Check that an infix expression is well formed, i.e. operands and operators alternate,
+// every parenthesis is matched and every "?" is followed by its ":"
+pub fn check_infix(infix: &[String]) -> Result<(), CalcError> {
+    // Track whether an operand is expected next
+    let mut expect_operand = true;
+    // Track the parentheses and conditionals that are still open and where, innermost last
+    let mut open: Vec<(&str, usize)> = Vec::new();
+
+    for (index, token) in infix.iter().enumerate() {
+        let innermost = open.last().map(|(token, _)| *token);
+
+        if expect_operand {
+            if string_is_number(token) {
+                // A number satisfies the expected operand
+                expect_operand = false;
+            } else if token == "(" {
+                // An opening parenthesis starts a nested operand
+                open.push(("(", index));
+            } else if !is_prefix_operator(token) {
+                // Only a prefix operator may precede an operand, anything else is an error
+                return Err(CalcError::at(ErrorKind::ExpectedOperand, index));
+            }
+        } else if is_postfix_operator(token) {
+            // A postfix operator applies to the operand before it, so another operator is expected
+            continue;
+        } else if token == ")" {
+            // A closing parenthesis ends a nested operand
+            if innermost != Some("(") {
+                return Err(CalcError::at(ErrorKind::UnmatchedParenthesis, index));
+            }
+            open.pop();
+        } else if token == "?" {
+            // A "?" starts the then branch of a conditional
+            open.push(("?", index));
+            expect_operand = true;
+        } else if token == ":" {
+            // A ":" ends the then branch and starts the else branch of a conditional
+            if innermost != Some("?") {
+                return Err(CalcError::at(ErrorKind::UnmatchedColon, index));
+            }
+            open.pop();
+            expect_operand = true;
+        } else if is_operator(token) && get_op_arity(token) == 2 {
+            // A binary operator must be followed by another operand
+            expect_operand = true;
+        } else {
+            // Anything else (a number, an opening parenthesis, ...) needs an operator before it
+            return Err(CalcError::at(ErrorKind::ExpectedOperator, index));
+        }
+    }
+
+    // The expression must end with an operand and close all of its parentheses and conditionals
+    if expect_operand {
+        Err(CalcError::at(
+            ErrorKind::Incomplete,
+            infix.len().saturating_sub(1),
+        ))
+    } else if let Some((token, index)) = open.pop() {
+        let kind = if token == "(" {
+            ErrorKind::UnclosedParenthesis
+        } else {
+            ErrorKind::UnclosedConditional
+        };
+        Err(CalcError::at(kind, index))
+    } else {
+        Ok(())
+    }
 }
 
 // Convert an infix expression to Reverse Polish Notation (RPN) using the shunting-yard algorithm
-fn shunting_yard(input: Vec<String>) -> Vec<String> {
+pub fn shunting_yard(input: Vec<String>) -> Vec<String> {
     // Initialize output queue and operator stack
     let mut output_queue: Vec<String> = Vec::new();
     let mut operator_stack: Vec<String> = Vec::new();
@@ -85,13 +285,39 @@ fn shunting_yard(input: Vec<String>) -> Vec<String> {
                     output_queue.push(op);
                 }
             }
+        } else if token == ":" {
+            // If token is the ":" of a conditional, its then branch is complete, so pop operators
+            // from operator stack and push them onto output queue until its "?" is encountered
+            while let Some(op) = operator_stack.pop() {
+                if op == "?" {
+                    break;
+                } else {
+                    output_queue.push(op);
+                }
+            }
+            // The conditional is pushed back as a whole, to be output once its else branch is complete
+            operator_stack.push("?:".to_string());
+        } else if is_postfix_operator(&token) {
+            // If token is a postfix operator, it binds tighter than any other operator
+            // to the operand before it, so push it onto the output queue
+            output_queue.push(token);
+        } else if is_prefix_operator(&token) {
+            // If token is a prefix operator, it applies to the operand that follows it,
+            // so push it onto the operator stack without popping anything
+            operator_stack.push(token);
         } else {
-            // If token is an operator
-            let o1 = token.clone();
+            // If token is an operator, or the "?" of a conditional which is popped like one
+            let o1 = if token == "?" {
+                "?:".to_string()
+            } else {
+                token.clone()
+            };
             if let Some(last) = operator_stack.last() {
                 let mut o2 = last.clone();
-                // While there are operators on the operator stack with higher precedence than o1
+                // While there are operators on the operator stack with higher precedence than o1,
+                // up to the enclosing parenthesis or then branch
                 while o2 != "("
+                    && o2 != "?"
                     && ((get_op_associativity(&o1) == 0
                         && get_op_precedence(&o1) <= get_op_precedence(&o2))
                         || (get_op_associativity(&o1) == 1
@@ -106,8 +332,8 @@ fn shunting_yard(input: Vec<String>) -> Vec<String> {
                     }
                 }
             }
-            // Push o1 onto operator stack
-            operator_stack.push(o1);
+            // Push o1 onto operator stack, a "?" as it is, marking the start of the then branch
+            operator_stack.push(token);
         }
     }
 
@@ -120,69 +346,437 @@ fn shunting_yard(input: Vec<String>) -> Vec<String> {
     output_queue
 }
 
-// Evaluate a mathematical expression in Reverse Polish Notation (RPN)
-fn evaluate_rpn(rpn: Vec<String>) -> Result<Int, bool> {
-    if rpn.len() < 2 {
-        return Err(false);
+// Parse a number, saturating at the limits of the Int type
+fn parse_number(token: &str) -> Int {
+    if token.starts_with('-') && token.parse::<Int>().is_err() {
+        // If token is a negative number and cannot be parsed as an Int, return Int::MIN
+        Int::MIN
+    } else if token.parse::<Int>().is_err() {
+        // If token is a positive number and cannot be parsed as an Int, return Int::MAX
+        Int::MAX
+    } else {
+        // Otherwise, return token as an Int
+        token.parse::<Int>().unwrap()
     }
-    // Initialize stack to hold intermediate results
-    let mut stack: Vec<Int> = Vec::new();
-    // Iterate over each token in the RPN expression
-    for token in rpn {
-        // Check if token is a number
-        if string_is_number(&token) {
-            if token.starts_with('-') && token.parse::<Int>().is_err() {
-                // If token is a negative number and cannot be parsed as an Int, push Int::MIN
-                stack.push(Int::MIN);
-            } else if token.parse::<Int>().is_err() {
-                // If token is a positive number and cannot be parsed as an Int, push Int::MAX
-                stack.push(Int::MAX);
-            } else {
-                // Otherwise, push token as an Int onto the stack
-                stack.push(token.parse::<Int>().unwrap());
-            }
-        } else {
-            // If token is an operator, pop two values from the stack
-            let b = stack.pop().unwrap_or(0);
-            let a = stack.pop().unwrap_or(0);
+}
 
-            // Calculate the result of the operation
-            let result = get_op_result(&token, a, b);
+// Check if a node is a percentage, i.e. an operand followed by "%"
+fn is_percentage(node: &Node) -> bool {
+    matches!(node, Node::Operator(op, _) if op == "%")
+}
 
-            if result == Err(false) {
-                return Err(false);
+// Evaluate an expression tree, noting down how each percentage in it was applied
+fn evaluate_tree(
+    node: &Node,
+    options: Options,
+    notes: &mut Vec<PercentNote>,
+) -> Result<Int, ErrorKind> {
+    let value = match node {
+        Node::Operand(token) => Ok(parse_number(token)),
+        Node::Operator(op, operands) => match op.as_str() {
+            // Logical operators and the conditional short-circuit,
+            // only evaluating the operands that decide the result
+            "&&" => Ok(from_bool(
+                evaluate_tree(&operands[0], options, notes)? != 0
+                    && evaluate_tree(&operands[1], options, notes)? != 0,
+            )),
+            "||" => Ok(from_bool(
+                evaluate_tree(&operands[0], options, notes)? != 0
+                    || evaluate_tree(&operands[1], options, notes)? != 0,
+            )),
+            "?:" => {
+                if evaluate_tree(&operands[0], options, notes)? != 0 {
+                    evaluate_tree(&operands[1], options, notes)
+                } else {
+                    evaluate_tree(&operands[2], options, notes)
+                }
             }
+            // A percentage on the right of a basic operation is applied the way a handheld
+            // calculator does, relative to the left operand
+            "+" | "-" | "x" | "÷" if is_percentage(&operands[1]) => {
+                let a = evaluate_tree(&operands[0], options, notes)?;
+                let Node::Operator(_, percent) = &operands[1] else {
+                    return Err(ErrorKind::Malformed);
+                };
+                let b = evaluate_tree(&percent[0], options, notes)?;
+                // Nothing is 0% of a whole
+                if op == "÷" && b == 0 {
+                    return Err(ErrorKind::DivisionByZero);
+                }
 
-            // Push the result back onto the stack
-            stack.push(result.unwrap());
-        }
+                let (result, note) = match op.as_str() {
+                    // Add or subtract the percentage of a, e.g. 200 + 10% is 220
+                    "+" => {
+                        let part = percent_of(a, b);
+                        (add(a, part), ("{a} + {b}% = {a} + {part}", [a, b, part]))
+                    }
+                    "-" => {
+                        let part = percent_of(a, b);
+                        (
+                            subtract(a, part),
+                            ("{a} - {b}% = {a} - {part}", [a, b, part]),
+                        )
+                    }
+                    // Take the percentage of a, e.g. 200 x 10% is 20
+                    "x" => (percent_of(a, b), ("{a} x {b}% = {b}% of {a}", [a, b, 0])),
+                    // Find the whole a is the percentage of, e.g. 50 ÷ 200% is 25
+                    _ => (
+                        percent_base(a, b),
+                        ("{a} ÷ {b}% = {a} x 100 ÷ {b}", [a, b, 0]),
+                    ),
+                };
+                notes.push(note);
+                Ok(result)
+            }
+            // A negated number is read as a negative one, so that the smallest number of the
+            // word is read back the way it is written, e.g. "-128" in 8 bits
+            "neg" if matches!(&operands[0], Node::Operand(_)) => {
+                let Node::Operand(token) = &operands[0] else {
+                    return Err(ErrorKind::Malformed);
+                };
+                let negated = match token.strip_prefix('-') {
+                    Some(positive) => positive.to_string(),
+                    None => format!("-{token}"),
+                };
+                Ok(parse_number(&negated))
+            }
+            // Anywhere else, a percentage is a hundredth of its operand
+            "%" => {
+                let b = evaluate_tree(&operands[0], options, notes)?;
+                notes.push(("{b}% = {b} ÷ 100", [0, b, 0]));
+                Ok(divide_rounded(b, 100))
+            }
+            // Any other operator needs all of its operands
+            _ => {
+                let b = evaluate_tree(operands.last().unwrap(), options, notes)?;
+                let a = if operands.len() == 2 {
+                    evaluate_tree(&operands[0], options, notes)?
+                } else {
+                    0
+                };
+                get_op_result(op, a, b, options)
+            }
+        },
+    }?;
+
+    // Fit every operand and every result into the word, before it is used any further
+    Ok(fit(value, options.word_size, options.overflow))
+}
+
+// Evaluate a mathematical expression in infix notation, noting down how each percentage
+// in it was applied
+fn evaluate(
+    input: Vec<String>,
+    options: Options,
+    notes: &mut Vec<PercentNote>,
+) -> Result<Int, CalcError> {
+    // Resolve negations and implied multiplications
+    let (infix, origins) = normalize_indexed(input, options);
+    // Check that the expression is well formed, pointing any error at the input token
+    check_infix(&infix).map_err(|error| CalcError {
+        index: error.index.and_then(|index| origins.get(index).copied()),
+        ..error
+    })?;
+
+    // A well formed expression always makes a tree, and evaluates unless it means something
+    // that has no result, so whatever fails past this point can't be pinned on a token
+    let unplaced = |kind| CalcError { kind, index: None };
+
+    // Convert infix expression to RPN using shunting-yard algorithm
+    let rpn = shunting_yard(infix);
+    // Build an expression tree from the RPN expression and evaluate it
+    let tree = tree_from_postfix(&rpn).map_err(|_| unplaced(ErrorKind::Malformed))?;
+    evaluate_tree(&tree, options, notes).map_err(unplaced)
+}
+
+// Calculate the value of a mathematical expression in infix notation, or return why it
+// can't be calculated
+pub fn calculate_value(input: Vec<String>, options: Options) -> Result<Int, CalcError> {
+    evaluate(input, options, &mut Vec::new())
+}
+
+// Describe how each percentage in a mathematical expression in infix notation is applied
+pub fn explain_percentages(input: Vec<String>, options: Options) -> Vec<PercentNote> {
+    let mut notes: Vec<PercentNote> = Vec::new();
+    _ = evaluate(input, options, &mut notes);
+    notes
+}
+
+// Note down each operation of an expression tree that is evaluated, in the order it is, with
+// its numbers written by a function, e.g. "3 x 4 = 12" then "2 + 12 = 14" for "2 + 3 x 4"
+fn trace_tree(
+    node: &Node,
+    options: Options,
+    write: &dyn Fn(Int) -> String,
+    steps: &mut Vec<String>,
+) {
+    let Node::Operator(op, operands) = node else {
+        return;
+    };
+    let value = |node: &Node| evaluate_tree(node, options, &mut Vec::new()).ok();
+
+    // A percentage applied to the left operand is a single operation
+    let is_relative = matches!(op.as_str(), "+" | "-" | "x" | "÷") && is_percentage(&operands[1]);
+    let shown: Vec<&Node> = match &operands[..] {
+        [a, Node::Operator(_, percent)] if is_relative => vec![a, &percent[0]],
+        _ => operands.iter().collect(),
+    };
+
+    // Only the operands that decide the result of a short-circuit are evaluated
+    let condition = value(&operands[0]).unwrap_or_default() != 0;
+    let evaluated: Vec<&Node> = match op.as_str() {
+        "&&" if !condition => vec![&operands[0]],
+        "||" if condition => vec![&operands[0]],
+        "?:" if condition => vec![&operands[0], &operands[1]],
+        "?:" => vec![&operands[0], &operands[2]],
+        _ => shown.clone(),
+    };
+    for operand in evaluated {
+        trace_tree(operand, options, write, steps);
     }
-    // Pop final result from stack and return it
-    Ok(stack.pop().unwrap())
+
+    // An operation that has no result, or has an operand that has none, isn't noted down
+    let (Some(values), Some(result)) = (
+        shown
+            .iter()
+            .map(|operand| value(operand))
+            .collect::<Option<Vec<Int>>>(),
+        value(node),
+    ) else {
+        return;
+    };
+    let values: Vec<String> = values.into_iter().map(write).collect();
+    let operation = match (op.as_str(), &values[..]) {
+        (_, [a, b]) if is_relative => format!("{a} {op} {b}%"),
+        ("%", [b]) => format!("{b}%"),
+        ("neg", [b]) => format!("-({b})"),
+        (_, [b]) => format!("{op}{b}"),
+        (_, [a, b]) => format!("{a} {op} {b}"),
+        (_, [condition, a, b]) => format!("{condition} ? {a} : {b}"),
+        _ => return,
+    };
+    steps.push(format!("{operation} = {}", write(result)));
 }
 
-// Calculate the result of a mathematical expression in infix notation
-pub fn calculate(input: Vec<String>, error_msg: &str) -> String {
-    // Check for special cases
-    if input.is_empty() {
-        // If empty, return empty string
-        return "".to_string();
-    } else if input.len() == 1 {
-        // If input contains one item, return the first item
-        return input.first().unwrap().to_string();
+// Describe each operation of a mathematical expression in infix notation in the order it is
+// evaluated, with its numbers written by a function, or describe none if it can't be evaluated
+pub fn trace(input: Vec<String>, options: Options, write: impl Fn(Int) -> String) -> Vec<String> {
+    let infix = normalize(input, options);
+    if check_infix(&infix).is_err() {
+        return Vec::new();
     }
+    let Ok(tree) = tree_from_postfix(&shunting_yard(infix)) else {
+        return Vec::new();
+    };
 
-    // Convert infix expression to RPN using shunting-yard algorithm
-    let rpn = shunting_yard(input);
-    // Evaluate RPN expression
-    let result = evaluate_rpn(rpn);
-
-    // Check if result is an error
-    if result == Err(false) {
-        // If it is, return the error message
-        error_msg.to_string()
-    } else {
-        // Otherwise, return the result
-        result.unwrap().to_string()
+    let mut steps: Vec<String> = Vec::new();
+    trace_tree(&tree, options, &write, &mut steps);
+    steps
+}
+
+// Complete a mathematical expression in infix notation that is still being typed, leaving out
+// the operators and parentheses at its end that wait for an operand and closing the
+// parentheses that are still open
+fn complete(input: Vec<String>) -> Vec<String> {
+    let mut input = input;
+
+    // Leave out what still waits for an operand, e.g. "2 + 3 x (" is read as "2 + 3"
+    while input.last().is_some_and(|token| {
+        token == "("
+            || token == "?"
+            || token == ":"
+            || is_operator(token) && !is_postfix_operator(token)
+    }) {
+        input.pop();
+    }
+
+    // Close the parentheses that are still open, e.g. "2 x (3 + 4" is read as "2 x (3 + 4)"
+    let opened = input.iter().filter(|token| *token == "(").count();
+    let closed = input.iter().filter(|token| *token == ")").count();
+    for _ in closed..opened {
+        input.push(")".to_string());
+    }
+
+    input
+}
+
+// Evaluate a mathematical expression in infix notation that is still being typed, or return
+// None if there is nothing to evaluate yet
+pub fn preview_value(input: Vec<String>, options: Options) -> Option<Int> {
+    evaluate(complete(input), options, &mut Vec::new()).ok()
+}
+
+// Evaluate a mathematical expression in infix notation that is still being typed for a preview
+// of its result, or return None if there is nothing to evaluate yet or it is a single number
+pub fn preview(input: Vec<String>, options: Options) -> Option<Int> {
+    let input = complete(input);
+
+    // A single number is its own result, so there is nothing to show
+    if input.is_empty() || is_single_number(&input) {
+        return None;
+    }
+
+    evaluate(input, options, &mut Vec::new()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::tokenize;
+
+    // Split text into tokens, which the tests expect to be readable
+    fn tokens(text: &str) -> Vec<String> {
+        tokenize(text).expect("Could not read the expression.")
+    }
+
+    // Calculate the value of an expression with the default options
+    fn value(text: &str) -> Result<Int, ErrorKind> {
+        calculate_value(tokens(text), Options::default()).map_err(|error| error.kind)
+    }
+
+    // Check an expression after resolving its negations and implied multiplications
+    fn check(text: &str) -> Result<(), CalcError> {
+        check_infix(&normalize(tokens(text), Options::default()))
+    }
+
+    #[test]
+    fn negation_binds_looser_than_a_power() {
+        assert_eq!(value("-2^2"), Ok(-4));
+        assert_eq!(value("- 2^2"), Ok(-4));
+        assert_eq!(value("(-2)^2"), Ok(4));
+        assert_eq!(value("2^-1"), value("2^(-1)"));
+    }
+
+    #[test]
+    fn check_infix_accepts_well_formed_expressions() {
+        assert_eq!(check("1 + 2 x 3"), Ok(()));
+        assert_eq!(check("-(1 + 2)%"), Ok(()));
+        assert_eq!(check("1 < 2 ? 3 : 4"), Ok(()));
+    }
+
+    #[test]
+    fn check_infix_finds_where_an_expression_goes_wrong() {
+        assert_eq!(check("1 +"), Err(CalcError::at(ErrorKind::Incomplete, 1)));
+        assert_eq!(
+            check("x 2"),
+            Err(CalcError::at(ErrorKind::ExpectedOperand, 0))
+        );
+        assert_eq!(
+            check("(1 + 2"),
+            Err(CalcError::at(ErrorKind::UnclosedParenthesis, 0))
+        );
+        assert_eq!(
+            check("1 + 2)"),
+            Err(CalcError::at(ErrorKind::UnmatchedParenthesis, 3))
+        );
+        assert_eq!(
+            check("1 ? 2"),
+            Err(CalcError::at(ErrorKind::UnclosedConditional, 1))
+        );
+        assert_eq!(
+            check("1 : 2"),
+            Err(CalcError::at(ErrorKind::UnmatchedColon, 1))
+        );
+    }
+
+    #[test]
+    fn two_numbers_are_not_multiplied() {
+        assert_eq!(value("2 3"), Err(ErrorKind::ExpectedOperator));
+        assert_eq!(value("2 (3)"), Ok(6));
+        assert_eq!(value("2 √ 16"), Ok(8));
+    }
+
+    #[test]
+    fn square_root_of_a_negative_number_is_a_domain_error() {
+        assert_eq!(value("√ 16"), Ok(4));
+        assert_eq!(value("√ -4"), Err(ErrorKind::NegativeSquareRoot));
+        assert!(ErrorKind::NegativeSquareRoot.is_domain_error());
+        assert_eq!(value("1 ÷ 0"), Err(ErrorKind::DivisionByZero));
+    }
+
+    #[test]
+    fn single_numbers_are_read_as_written() {
+        assert_eq!(single_number_value(&tokens("100000")), Some(100000));
+        assert_eq!(single_number_value(&tokens("-0xff")), Some(-255));
+        assert_eq!(single_number_value(&tokens("1 + 1")), None);
+    }
+
+    #[test]
+    fn comparisons_bind_looser_than_arithmetic() {
+        assert_eq!(value("1 + 1 == 2"), Ok(1));
+        assert_eq!(value("2 x 3 < 5"), Ok(0));
+        assert_eq!(value("1 < 2 == 1"), Ok(1));
+        assert_eq!(value("1 != 1 || 2 >= 2 && 3 <= 3"), Ok(1));
+        assert_eq!(value("!0"), Ok(1));
+        assert_eq!(value("!5 + 1"), Ok(1));
+        assert_eq!(value("!(5 - 5)"), Ok(1));
+    }
+
+    #[test]
+    fn conditionals_nest_to_the_right() {
+        assert_eq!(value("1 ? 2 : 3"), Ok(2));
+        assert_eq!(value("0 ? 2 : 3"), Ok(3));
+        // "a ? b : c ? d : e" is "a ? b : (c ? d : e)"
+        assert_eq!(value("0 ? 1 : 0 ? 2 : 3"), Ok(3));
+        assert_eq!(value("0 ? 1 : 1 ? 2 : 3"), Ok(2));
+        assert_eq!(value("1 ? 0 ? 4 : 5 : 6"), Ok(5));
+        assert_eq!(value("1 + 1 == 2 ? 10 : 20"), Ok(10));
+    }
+
+    #[test]
+    fn logical_operators_and_conditionals_short_circuit() {
+        assert_eq!(value("1 || 1 ÷ 0"), Ok(1));
+        assert_eq!(value("0 && 1 ÷ 0"), Ok(0));
+        assert_eq!(value("1 ? 2 : 1 ÷ 0"), Ok(2));
+        assert_eq!(value("0 || 1 ÷ 0"), Err(ErrorKind::DivisionByZero));
+        assert_eq!(value("0 ? 2 : 1 ÷ 0"), Err(ErrorKind::DivisionByZero));
+    }
+
+    #[test]
+    fn conditionals_need_both_halves() {
+        assert_eq!(value("1 : 2"), Err(ErrorKind::UnmatchedColon));
+        assert_eq!(value("1 ? 2"), Err(ErrorKind::UnclosedConditional));
+        assert_eq!(value("(1 ? 2) : 3"), Err(ErrorKind::UnmatchedParenthesis));
+    }
+
+    #[test]
+    fn percentages_are_relative_to_the_left_operand() {
+        assert_eq!(value("200 + 10%"), Ok(220));
+        assert_eq!(value("200 - 10%"), Ok(180));
+        assert_eq!(value("200 x 10%"), Ok(20));
+        assert_eq!(value("50 ÷ 200%"), Ok(25));
+        assert_eq!(value("7 ÷ 0%"), Err(ErrorKind::DivisionByZero));
+        let notes = explain_percentages(tokens("200 + 10%"), Options::default());
+        assert_eq!(notes, [("{a} + {b}% = {a} + {part}", [200, 10, 20])]);
+    }
+
+    #[test]
+    fn remainders_take_the_sign_of_the_dividend() {
+        assert_eq!(value("7 mod 3"), Ok(1));
+        assert_eq!(value("-7 mod 3"), Ok(-1));
+        assert_eq!(value("7 mod -3"), Ok(1));
+        assert_eq!(value("7 mod 0"), Err(ErrorKind::DivisionByZero));
+    }
+
+    #[test]
+    fn previews_complete_what_is_still_being_typed() {
+        let preview_of = |text: &str| preview(tokens(text), Options::default());
+        assert_eq!(preview_of("2 + 3 x"), Some(5));
+        assert_eq!(preview_of("2 x (3 + 4"), Some(14));
+        assert_eq!(preview_of("2 x ((3 + 4) x ("), Some(14));
+        assert_eq!(preview_of("1 < 2 ?"), Some(1));
+        // A conditional without its else branch has nothing to show until one is typed
+        assert_eq!(preview_of("1 < 2 ? 3 :"), None);
+        assert_eq!(preview_of("1 ? 2 : 3"), Some(2));
+    }
+
+    #[test]
+    fn previews_of_single_numbers_are_none() {
+        let preview_of = |text: &str| preview(tokens(text), Options::default());
+        assert_eq!(preview_of(""), None);
+        assert_eq!(preview_of("42"), None);
+        assert_eq!(preview_of("-42"), None);
+        assert_eq!(preview_of("42 +"), None);
+        assert_eq!(preview_value(tokens("42 +"), Options::default()), Some(42));
     }
 }
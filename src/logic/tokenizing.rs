@@ -0,0 +1,204 @@
+use crate::types::*;
+
+use std::collections::BTreeMap;
+
+// Define the symbols that make up operators and parentheses, longest first so that
+// e.g. "<=" is not read as "<" followed by "="
+const SYMBOLS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "÷", "^", "√", "%", "<", ">", "!", "?", ":", "(",
+    ")",
+];
+
+// Define the words that name operators
+const WORDS: [&str; 2] = ["x", "mod"];
+
+// Define other ways of typing symbols, (alias, symbol)
+const ALIASES: [(char, &str); 4] = [('*', "x"), ('×', "x"), ('/', "÷"), ('−', "-")];
+
+// Define the prefixes of numbers written in other bases, e.g. "0x40", (prefix, radix)
+const RADIX_PREFIXES: [(char, u32); 3] = [('x', 16), ('o', 8), ('b', 2)];
+
+// Define a token of an expression and the range of characters it was read from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Check if a word can name a variable, i.e. it is made of letters and names no operator
+pub fn is_variable_name(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(char::is_alphabetic) && !WORDS.contains(&word)
+}
+
+// Split the text of an expression into tokens, or return the position of the first
+// character that can't be read
+pub fn scan(text: &str) -> Result<Vec<Token>, usize> {
+    scan_with(text, &BTreeMap::new())
+}
+
+// Split the text of an expression into tokens, reading the names of variables as their
+// values, or return the position of the first character that can't be read
+pub fn scan_with(text: &str, variables: &BTreeMap<String, Int>) -> Result<Vec<Token>, usize> {
+    // Initialize output vector
+    let mut tokens: Vec<Token> = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            // Whitespace only separates tokens
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() {
+            // Check for a prefix that is followed by a digit of its base
+            let radix = chars
+                .get(i + 1)
+                .filter(|_| c == '0')
+                .and_then(|prefix| {
+                    RADIX_PREFIXES
+                        .iter()
+                        .find(|(p, _)| *p == prefix.to_ascii_lowercase())
+                })
+                .map(|(_, radix)| *radix)
+                .filter(|radix| chars.get(i + 2).is_some_and(|c| c.is_digit(*radix)));
+
+            let digits: String = if let Some(radix) = radix {
+                // Read all digits of a number in another base, skipping the prefix
+                i += 2;
+                let digits_start = i;
+                while i < chars.len() && chars[i].is_digit(radix) {
+                    i += 1;
+                }
+                let digits: String = chars[digits_start..i].iter().collect();
+
+                // The digits are the bits of the number, which is then fitted into the word
+                // like any other, so e.g. "0xffffffff" is -1 in 32 bits when results wrap
+                // around, and the rest of the calculator reads it in decimal
+                match Uint::from_str_radix(&digits, radix) {
+                    Ok(value) => (value as Int).to_string(),
+                    Err(_) => return Err(start),
+                }
+            } else {
+                // Read all digits of a number
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                chars[start..i].iter().collect()
+            };
+
+            tokens.push(Token {
+                text: digits,
+                start,
+                end: i,
+            });
+            continue;
+        } else if c.is_alphabetic() {
+            // Read all letters of a word, which must be a named operator or a variable
+            while i < chars.len() && chars[i].is_alphabetic() {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let text = if WORDS.contains(&word.as_str()) {
+                word
+            } else if let Some(value) = variables.get(&word) {
+                // A variable stands for its value, which is read like a number
+                value.to_string()
+            } else {
+                return Err(start);
+            };
+
+            tokens.push(Token {
+                text,
+                start,
+                end: i,
+            });
+            continue;
+        }
+
+        // Read the longest symbol, or the symbol an alias stands for
+        let symbol = SYMBOLS
+            .iter()
+            .find(|symbol| chars[i..].starts_with(&symbol.chars().collect::<Vec<char>>()))
+            .map(|symbol| (symbol.to_string(), symbol.chars().count()))
+            .or_else(|| {
+                ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == c)
+                    .map(|(_, symbol)| (symbol.to_string(), 1))
+            });
+
+        if let Some((symbol, length)) = symbol {
+            i += length;
+            tokens.push(Token {
+                text: symbol,
+                start,
+                end: i,
+            });
+        } else {
+            // Otherwise, the character is not supported
+            return Err(start);
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Split the text of an expression into the texts of its tokens, without the ranges they were
+// read from, or return the position of the first character that can't be read
+pub fn tokenize(text: &str) -> Result<Vec<String>, usize> {
+    scan(text).map(|tokens| tokens.into_iter().map(|token| token.text).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Split text into the texts of its tokens
+    fn texts(text: &str) -> Vec<String> {
+        tokenize(text).expect("Could not read the expression.")
+    }
+
+    #[test]
+    fn minus_is_always_its_own_token() {
+        assert_eq!(texts("-2^2"), ["-", "2", "^", "2"]);
+        assert_eq!(texts("- 2^2"), texts("-2^2"));
+        assert_eq!(texts("3-2"), ["3", "-", "2"]);
+        assert_eq!(texts("3 −2"), ["3", "-", "2"]);
+    }
+
+    #[test]
+    fn numbers_can_have_a_base_prefix() {
+        assert_eq!(texts("0xff + 0b101"), ["255", "+", "5"]);
+        assert_eq!(texts("0o17"), ["15"]);
+        // A prefix without a digit of its base after it is a zero and a multiplication
+        assert_eq!(texts("0x"), ["0", "x"]);
+    }
+
+    #[test]
+    fn only_ascii_digits_are_numbers() {
+        assert_eq!(tokenize("١٢"), Err(0));
+        assert_eq!(tokenize("2 + ²"), Err(4));
+    }
+
+    #[test]
+    fn tokens_keep_their_ranges() {
+        let tokens = scan("12 * 3").unwrap();
+        let ranges: Vec<(usize, usize)> = tokens.iter().map(|t| (t.start, t.end)).collect();
+        assert_eq!(ranges, [(0, 2), (3, 4), (5, 6)]);
+        assert_eq!(tokens[1].text, "x");
+    }
+
+    #[test]
+    fn variables_are_read_as_their_values() {
+        let variables = BTreeMap::from([("mask".to_string(), 255)]);
+        let tokens = scan_with("mask + 1", &variables).unwrap();
+        assert_eq!(tokens[0].text, "255");
+        assert_eq!(scan_with("other", &variables), Err(0));
+        assert!(is_variable_name("mask"));
+        assert!(!is_variable_name("mod"));
+    }
+}
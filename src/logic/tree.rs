@@ -0,0 +1,72 @@
+use super::parsing::*;
+
+// Define a node of an expression tree, which all notations can be built from and written to
+pub enum Node {
+    Operand(String),
+    Operator(String, Vec<Node>),
+}
+
+// Build an expression tree from a postfix expression
+pub fn tree_from_postfix(postfix: &[String]) -> Result<Node, bool> {
+    // Initialize stack to hold the subtrees built so far
+    let mut stack: Vec<Node> = Vec::new();
+
+    for token in postfix {
+        if string_is_number(token) {
+            // If token is a number, push it onto the stack as a leaf
+            stack.push(Node::Operand(token.clone()));
+        } else if is_operator(token) {
+            // If token is an operator, its operands are the last subtrees on the stack
+            let arity = get_op_arity(token) as usize;
+            if stack.len() < arity {
+                return Err(false);
+            }
+            let operands = stack.split_off(stack.len() - arity);
+            stack.push(Node::Operator(token.clone(), operands));
+        } else {
+            // Otherwise, token is not supported
+            return Err(false);
+        }
+    }
+
+    // A well formed expression leaves exactly one tree on the stack
+    if stack.len() == 1 {
+        Ok(stack.pop().unwrap())
+    } else {
+        Err(false)
+    }
+}
+
+// Build an expression tree from a prefix expression
+pub fn tree_from_prefix(prefix: &[String]) -> Result<Node, bool> {
+    // Initialize stack to hold the subtrees built so far
+    let mut stack: Vec<Node> = Vec::new();
+
+    // Read the expression backwards, so operands are seen before their operator
+    for token in prefix.iter().rev() {
+        if string_is_number(token) {
+            // If token is a number, push it onto the stack as a leaf
+            stack.push(Node::Operand(token.clone()));
+        } else if is_operator(token) {
+            // If token is an operator, its operands are the last subtrees on the stack,
+            // with the first operand on top
+            let arity = get_op_arity(token) as usize;
+            if stack.len() < arity {
+                return Err(false);
+            }
+            let mut operands = stack.split_off(stack.len() - arity);
+            operands.reverse();
+            stack.push(Node::Operator(token.clone(), operands));
+        } else {
+            // Otherwise, token is not supported
+            return Err(false);
+        }
+    }
+
+    // A well formed expression leaves exactly one tree on the stack
+    if stack.len() == 1 {
+        Ok(stack.pop().unwrap())
+    } else {
+        Err(false)
+    }
+}
@@ -1,53 +1,379 @@
+mod cli;
 mod components;
+mod config;
+mod files;
+mod history;
+mod i18n;
+mod layout;
 mod logic;
+mod memory;
+mod theme;
 mod types;
 
-use components::body_box;
+use components::{body_box, header_bar, preferences_window, tab_label};
+use config::{Config, Mode, MODES};
+use history::History;
+use i18n::gettext;
+use logic::Options;
+use memory::Memory;
 
-use gtk::gdk::Display;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gtk::gio::SimpleAction;
+use gtk::glib::{self, clone, VariantTy};
 use gtk::prelude::*;
-use gtk::{style_context_add_provider_for_display, Application, ApplicationWindow, CssProvider};
-use leptos::{create_runtime, create_scope, Scope};
+use gtk::{Application, ApplicationWindow, Notebook, Widget, Window};
+use leptos::{
+    create_effect, create_memo, create_runtime, create_rw_signal, create_scope, create_signal,
+    on_cleanup, ReadSignal, RwSignal, Scope, SignalGet, SignalGetUntracked, SignalSet,
+    SignalUpdate, SignalWith, SignalWithUntracked,
+};
 
 const APP_ID: &str = "dev.vannrr.calculator";
 
 const WINDOW_TITLE: &str = "Calculator";
 const WINDOW_WIDTH: i32 = 300;
 
+// Note whether a tab keeps the saved history and memory, only one does at a time so that tabs
+// never overwrite each other's, the others keep theirs until they are closed
+static SAVED_TAB_OPEN: AtomicBool = AtomicBool::new(false);
+
+// Define the state of a tab, every tab has its own expression, history, memory, mode and
+// options, so that e.g. one can work in hexadecimal while another works in decimal
+#[derive(Clone, Copy)]
+struct Tab {
+    history: RwSignal<History>,
+    memory: RwSignal<Memory>,
+    mode: RwSignal<Mode>,
+    options: RwSignal<Options>,
+}
+
 fn main() {
+    // evaluate on the command line instead of opening the calculator when given arguments
+    if let Some(status) = cli::run(std::env::args().skip(1).collect()) {
+        std::process::exit(status);
+    }
+
     _ = create_scope(create_runtime(), |cx| {
+        // the settings are shared by every window, and saved with every change
+        let config = create_rw_signal(cx, Config::load());
+        create_effect(cx, move |_| config.with(Config::save));
+
         let app = Application::builder().application_id(APP_ID).build();
 
-        app.connect_startup(|_| load_css());
-        app.connect_activate(move |app| build_ui(cx, app));
+        app.connect_startup(move |app| {
+            theme::load(cx, app, config);
+            app.add_action(&new_window_action(cx, app, config));
+        });
+        app.connect_activate(move |app| build_window(cx, app, config));
 
         app.set_accels_for_action("window.close", &["<Ctrl>q", "Escape"]);
+        app.set_accels_for_action("app.new-window", &["<Ctrl>n"]);
+        app.set_accels_for_action("win.new-tab", &["<Ctrl>t"]);
+        app.set_accels_for_action("win.close-tab", &["<Ctrl>w"]);
+        app.set_accels_for_action("win.preferences", &["<Ctrl>comma"]);
+        for (index, (_, name, _)) in MODES.iter().enumerate() {
+            app.set_accels_for_action(
+                &format!("win.mode::{name}"),
+                &[&format!("<Ctrl>{}", index + 1)],
+            );
+        }
 
         app.run();
     });
 }
 
-fn load_css() {
-    let provider = CssProvider::new();
-    provider.load_from_data(include_str!("style.css"));
+fn new_window_action(cx: Scope, app: &Application, config: RwSignal<Config>) -> SimpleAction {
+    let action = SimpleAction::new("new-window", None);
+
+    action.connect_activate(clone!(@weak app => move |_, _| {
+        build_window(cx, &app, config);
+    }));
+
+    action
+}
+
+fn new_tab_action(
+    cx: Scope,
+    notebook: &Notebook,
+    config: RwSignal<Config>,
+    width: ReadSignal<i32>,
+    current: RwSignal<Option<Tab>>,
+) -> SimpleAction {
+    let action = SimpleAction::new("new-tab", None);
+
+    action.connect_activate(clone!(@weak notebook => move |_, _| {
+        add_tab(cx, &notebook, config, width, current);
+    }));
+
+    action
+}
+
+fn close_tab_action(notebook: &Notebook) -> SimpleAction {
+    let action = SimpleAction::new("close-tab", None);
+
+    // the tab label has a close button, which knows how to close its tab
+    action.connect_activate(clone!(@weak notebook => move |_, _| {
+        if let Some(close_button) = notebook
+            .nth_page(notebook.current_page())
+            .and_then(|page| notebook.tab_label(&page))
+            .and_then(|tab_label| tab_label.last_child())
+        {
+            close_button.activate();
+        }
+    }));
+
+    action
+}
+
+// The actions below work on the tab that is shown, there is one from the moment a window opens
+
+fn implicit_multiplication_action(current: RwSignal<Option<Tab>>) -> SimpleAction {
+    let action = SimpleAction::new_stateful("implicit-multiplication", None, true.to_variant());
+
+    action.connect_change_state(move |_, state| {
+        let enabled = state.and_then(|state| state.get::<bool>());
+        if let (Some(tab), Some(enabled)) = (current.get_untracked(), enabled) {
+            tab.options
+                .update(|options| options.implicit_multiplication = enabled);
+        }
+    });
+
+    action
+}
+
+fn clear_history_action(current: RwSignal<Option<Tab>>) -> SimpleAction {
+    let action = SimpleAction::new("clear-history", None);
+
+    action.connect_activate(move |_, _| {
+        if let Some(tab) = current.get_untracked() {
+            tab.history.update(|history| history.entries.clear());
+        }
+    });
 
-    style_context_add_provider_for_display(
-        &Display::default().expect("Could not connect to a display."),
-        &provider,
-        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    action
+}
+
+fn mode_action(current: RwSignal<Option<Tab>>) -> SimpleAction {
+    let action = SimpleAction::new_stateful(
+        "mode",
+        Some(VariantTy::STRING),
+        config::to_name(&MODES, Mode::Basic).to_variant(),
     );
+
+    action.connect_change_state(move |_, state| {
+        let name = state.and_then(|state| state.get::<String>());
+        let mode = name.and_then(|name| config::from_name(&MODES, &name));
+        if let (Some(tab), Some(mode)) = (current.get_untracked(), mode) {
+            tab.mode.set(mode);
+        }
+    });
+
+    action
+}
+
+fn preferences_action(
+    config: RwSignal<Config>,
+    current: RwSignal<Option<Tab>>,
+    window: &ApplicationWindow,
+) -> SimpleAction {
+    let action = SimpleAction::new("preferences", None);
+
+    action.connect_activate(clone!(@weak window => move |_, _| {
+        if let Some(tab) = current.get_untracked() {
+            preferences_window::new(config, tab.options, &window).present();
+        }
+    }));
+
+    action
+}
+
+// Open a tab at the end of a notebook and show it, its history and memory start from the saved
+// ones if no other tab keeps them, or else empty, and its mode and options from the last picked
+fn add_tab(
+    cx: Scope,
+    notebook: &Notebook,
+    config: RwSignal<Config>,
+    width: ReadSignal<i32>,
+    current: RwSignal<Option<Tab>>,
+) {
+    // the tab lives in its own scope, so that everything it keeps track of goes with it
+    let ((tab, page, tab_label), disposer) = cx.run_child_scope(|cx| {
+        let (history_limit, mode, options) =
+            config.with_untracked(|config| (config.history_limit, config.mode, config.options));
+        let keeps_saved = !SAVED_TAB_OPEN.swap(true, Ordering::Relaxed);
+        let (history, memory) = if keeps_saved {
+            (History::load(history_limit), Memory::load())
+        } else {
+            (History::new(history_limit), Memory::default())
+        };
+        let tab = Tab {
+            history: create_rw_signal(cx, history),
+            memory: create_rw_signal(cx, memory),
+            mode: create_rw_signal(cx, mode),
+            options: create_rw_signal(cx, options),
+        };
+
+        // keep the saved history and memory up to date with every change, and hand them over
+        // to the next tab opened once this one is closed
+        if keeps_saved {
+            create_effect(cx, move |_| tab.history.with(History::save));
+            create_effect(cx, move |_| tab.memory.with(Memory::save));
+            on_cleanup(cx, || SAVED_TAB_OPEN.store(false, Ordering::Relaxed));
+        }
+
+        // drop the oldest entries as soon as the history is told to keep fewer
+        create_effect(cx, move |previous_limit: Option<usize>| {
+            let limit = config.with(|config| config.history_limit);
+            if previous_limit.is_some_and(|previous_limit| previous_limit != limit) {
+                tab.history.update(|history| history.set_limit(limit));
+            }
+            limit
+        });
+
+        // open the next tab in the mode and with the options picked last
+        create_effect(cx, move |_| {
+            let (mode, options) = (tab.mode.get(), tab.options.get());
+            config.update(|config| {
+                config.mode = mode;
+                config.options = options;
+            });
+        });
+
+        // the settings as the tab sees them, with its own mode and options
+        let tab_config = create_memo(cx, move |_| Config {
+            options: tab.options.get(),
+            mode: tab.mode.get(),
+            ..config.get()
+        });
+        let page = body_box::new(
+            cx,
+            tab_config,
+            tab.history,
+            tab.memory,
+            width,
+            tab.mode.read_only(),
+        );
+
+        let tab_label = tab_label::new(
+            cx,
+            tab.mode,
+            clone!(@weak notebook, @weak page => move || close_tab(&notebook, &page)),
+        );
+
+        (tab, page, tab_label)
+    });
+
+    // have the actions of the window work on the tab while it is shown
+    notebook.connect_switch_page(clone!(@weak page => move |_, shown, _| {
+        if *shown == page {
+            current.set(Some(tab));
+        }
+    }));
+
+    // forget the tab once it is closed
+    let disposer = Cell::new(Some(disposer));
+    notebook.connect_page_removed(clone!(@weak page => move |_, removed, _| {
+        if *removed == page {
+            if let Some(disposer) = disposer.take() {
+                disposer.dispose();
+            }
+        }
+    }));
+
+    let index = notebook.append_page(&page, Some(&tab_label));
+    notebook.set_tab_reorderable(&page, true);
+    notebook.set_current_page(Some(index));
+}
+
+// Close a tab, and its window along with its last tab
+fn close_tab(notebook: &Notebook, page: &impl IsA<Widget>) {
+    if notebook.n_pages() > 1 {
+        notebook.remove_page(notebook.page_num(page));
+    } else if let Some(window) = notebook.root().and_downcast::<Window>() {
+        window.close();
+    }
 }
 
-fn build_ui(cx: Scope, app: &Application) {
-    let body_box = body_box::new(cx, WINDOW_WIDTH);
+fn build_window(cx: Scope, app: &Application, config: RwSignal<Config>) {
+    // the window lives in its own scope, along with its tabs, so that they go with it
+    let (window, disposer) = cx.run_child_scope(|cx| {
+        let (width, set_width) = create_signal(cx, WINDOW_WIDTH);
+        let current = create_rw_signal(cx, None::<Tab>);
+
+        // only show the tabs once there are more than one
+        let notebook = Notebook::builder()
+            .show_tabs(false)
+            .show_border(false)
+            .scrollable(true)
+            .build();
+        notebook
+            .connect_page_added(|notebook, _, _| notebook.set_show_tabs(notebook.n_pages() > 1));
+        notebook
+            .connect_page_removed(|notebook, _, _| notebook.set_show_tabs(notebook.n_pages() > 1));
+
+        add_tab(cx, &notebook, config, width, current);
+
+        let header_bar = header_bar::new();
+
+        let window = ApplicationWindow::builder()
+            .application(app)
+            .title(gettext(WINDOW_TITLE))
+            .default_width(WINDOW_WIDTH)
+            .child(&notebook)
+            .build();
 
-    let window = ApplicationWindow::builder()
-        .application(app)
-        .title(WINDOW_TITLE)
-        .default_width(WINDOW_WIDTH)
-        .resizable(false)
-        .child(&body_box)
-        .build();
+        window.set_titlebar(Some(&header_bar));
+
+        // follow the width of the window whenever its surface is resized, reading it once the
+        // window has been laid out at the new size
+        window.connect_realize(move |window| {
+            window
+                .surface()
+                .connect_width_notify(clone!(@weak window => move |_| {
+                    glib::idle_add_local_once(clone!(@weak window => move || {
+                        if window.width() != width.get_untracked() {
+                            set_width.set(window.width());
+                        }
+                    }));
+                }));
+        });
+
+        let implicit_multiplication_action = implicit_multiplication_action(current);
+        let mode_action = mode_action(current);
+        // show the setting and the mode of the tab in the menu and the header bar, however
+        // they are changed
+        create_effect(cx, {
+            let implicit_multiplication_action = implicit_multiplication_action.clone();
+            let mode_action = mode_action.clone();
+            move |_| {
+                if let Some(tab) = current() {
+                    let enabled = tab.options.with(|options| options.implicit_multiplication);
+                    implicit_multiplication_action.set_state(enabled.to_variant());
+                    let mode = config::to_name(&MODES, tab.mode.get());
+                    mode_action.set_state(mode.to_variant());
+                }
+            }
+        });
+
+        window.add_action(&implicit_multiplication_action);
+        window.add_action(&mode_action);
+        window.add_action(&clear_history_action(current));
+        window.add_action(&preferences_action(config, current, &window));
+        window.add_action(&new_tab_action(cx, &notebook, config, width, current));
+        window.add_action(&close_tab_action(&notebook));
+
+        window
+    });
+
+    // forget the window and its tabs once it is closed
+    let disposer = Cell::new(Some(disposer));
+    window.connect_destroy(move |_| {
+        if let Some(disposer) = disposer.take() {
+            disposer.dispose();
+        }
+    });
 
     window.present();
 }
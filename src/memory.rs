@@ -0,0 +1,72 @@
+use crate::logic::{add, fit, subtract, Options};
+use crate::types::Int;
+
+use crate::files::{data_file, save_lines};
+
+use std::collections::BTreeMap;
+use std::fs;
+
+// Define the storage registers, the one the memory keys work on followed by the named slots
+pub const REGISTERS: [&str; 10] = ["M", "M1", "M2", "M3", "M4", "M5", "M6", "M7", "M8", "M9"];
+
+// Define the contents of the storage registers, an empty register has no value
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Memory {
+    pub values: BTreeMap<String, Int>,
+}
+
+impl Memory {
+    // Load the registers saved by an earlier session
+    pub fn load() -> Memory {
+        // A missing or unreadable file just means all registers are empty
+        let values = fs::read_to_string(data_file("memory"))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(name, _)| REGISTERS.contains(name))
+            .filter_map(|(name, value)| Some((name.to_string(), value.parse().ok()?)))
+            .collect();
+
+        Memory { values }
+    }
+
+    // Save the registers, one per line with the name and value separated by a tab
+    pub fn save(&self) {
+        save_lines(
+            &data_file("memory"),
+            self.values
+                .iter()
+                .map(|(name, value)| format!("{name}\t{value}")),
+        );
+    }
+
+    // Get the value of a register
+    pub fn get(&self, name: &str) -> Option<Int> {
+        self.values.get(name).copied()
+    }
+
+    // Store a value in a register, replacing its contents
+    pub fn store(&mut self, name: &str, value: Int) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    // Empty a register
+    pub fn clear(&mut self, name: &str) {
+        self.values.remove(name);
+    }
+
+    // Add a value to a register, fitting the sum into the word the way the calculation does
+    pub fn add(&mut self, name: &str, value: Int, options: Options) {
+        let sum = add(self.get(name).unwrap_or(0), value);
+        let sum = fit(sum, options.word_size, options.overflow);
+        self.store(name, sum);
+    }
+
+    // Subtract a value from a register, fitting the difference into the word the way the
+    // calculation does
+    pub fn subtract(&mut self, name: &str, value: Int, options: Options) {
+        let difference = subtract(self.get(name).unwrap_or(0), value);
+        let difference = fit(difference, options.word_size, options.overflow);
+        self.store(name, difference);
+    }
+}
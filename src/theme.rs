@@ -0,0 +1,126 @@
+use crate::config::Config;
+use crate::files::config_file;
+
+use gtk::gdk::Display;
+use gtk::gio::{Cancellable, File, FileMonitorFlags};
+use gtk::glib::{self, clone};
+use gtk::prelude::*;
+use gtk::{style_context_add_provider_for_display, Application, CssProvider, Settings};
+use leptos::{create_effect, RwSignal, Scope, SignalWith, SignalWithUntracked};
+
+use std::fs;
+use std::path::PathBuf;
+
+// Define the built-in themes, which give the colours of the calculator for the light, dark and
+// high contrast variants of the system theme
+const LIGHT_THEME: &str = include_str!("themes/light.css");
+const DARK_THEME: &str = include_str!("themes/dark.css");
+const HIGH_CONTRAST_THEME: &str = include_str!("themes/high-contrast.css");
+
+// Define the themes that can be picked, the system theme picks the built-in theme matching it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+// Get the stylesheet the user can restyle the calculator with
+fn user_css_path() -> PathBuf {
+    config_file("style.css")
+}
+
+// Pick the built-in theme for the picked theme, or the one that matches the system theme
+fn theme_css(settings: &Settings, theme: Theme) -> &'static str {
+    let theme_name = settings.gtk_theme_name().unwrap_or_default();
+    let prefers_dark =
+        settings.is_gtk_application_prefer_dark_theme() || theme_name.ends_with("-dark");
+
+    match theme {
+        Theme::Light => LIGHT_THEME,
+        Theme::Dark => DARK_THEME,
+        Theme::HighContrast => HIGH_CONTRAST_THEME,
+        Theme::System if theme_name.starts_with("HighContrast") => HIGH_CONTRAST_THEME,
+        Theme::System if prefers_dark => DARK_THEME,
+        Theme::System => LIGHT_THEME,
+    }
+}
+
+// Load the user stylesheet, or nothing if there is none
+fn load_user_css(provider: &CssProvider) {
+    let path = user_css_path();
+    if path.exists() {
+        provider.load_from_path(path);
+    } else {
+        provider.load_from_data("");
+    }
+}
+
+pub fn load(cx: Scope, app: &Application, config: RwSignal<Config>) {
+    let display = Display::default().expect("Could not connect to a display.");
+    let settings = Settings::for_display(&display);
+
+    let app_provider = CssProvider::new();
+    app_provider.load_from_data(include_str!("style.css"));
+
+    // load the picked theme, as soon as it is picked
+    let theme_provider = CssProvider::new();
+    create_effect(cx, {
+        let theme_provider = theme_provider.clone();
+        let settings = settings.clone();
+        move |_| {
+            let theme = config.with(|config| config.theme);
+            theme_provider.load_from_data(theme_css(&settings, theme));
+        }
+    });
+
+    let user_provider = CssProvider::new();
+    load_user_css(&user_provider);
+
+    // the theme adds to the style of the calculator, and the user stylesheet overrides both
+    style_context_add_provider_for_display(
+        &display,
+        &app_provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+    style_context_add_provider_for_display(
+        &display,
+        &theme_provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+    );
+    style_context_add_provider_for_display(
+        &display,
+        &user_provider,
+        gtk::STYLE_PROVIDER_PRIORITY_USER,
+    );
+
+    // follow the system theme when it changes
+    settings.connect_gtk_theme_name_notify(clone!(@weak theme_provider => move |settings| {
+        let theme = config.with_untracked(|config| config.theme);
+        theme_provider.load_from_data(theme_css(settings, theme));
+    }));
+    settings.connect_gtk_application_prefer_dark_theme_notify(
+        clone!(@weak theme_provider => move |settings| {
+            let theme = config.with_untracked(|config| config.theme);
+            theme_provider.load_from_data(theme_css(settings, theme));
+        }),
+    );
+
+    // reload the user stylesheet whenever it changes on disk, watching its directory
+    // requires it to exist, even while the stylesheet doesn't
+    let path = user_css_path();
+    if let Some(dir) = path.parent() {
+        _ = fs::create_dir_all(dir);
+    }
+    if let Ok(monitor) =
+        File::for_path(path).monitor_file(FileMonitorFlags::NONE, None::<&Cancellable>)
+    {
+        monitor.connect_changed(move |_, _, _, _| load_user_css(&user_provider));
+
+        // keep watching for as long as the application runs
+        app.connect_shutdown(move |_| {
+            monitor.cancel();
+        });
+    }
+}
@@ -1,4 +1,6 @@
 // Define custom types for signed and unsigned integers
-pub type Int = i32;
-pub type Uint = u32;
-pub type Bint = i64;
+// Int is wider than the largest word size, so that a result can be fitted into the word after
+// it is calculated, see logic::Options
+pub type Int = i64;
+pub type Uint = u64;
+pub type Bint = i128;